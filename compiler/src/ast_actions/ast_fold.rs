@@ -2,26 +2,283 @@ extern crate lang_parser;
 
 use lang_parser::ast::*;
 
+#[allow(dead_code)]
 pub trait AstFold {
-    fn fold_file(&mut self, n: File) -> File { n }
+    fn fold_file(&mut self, mut n: File) -> File {
+        let entities = n.get_entities().drain(..).map(|entity| match entity {
+            FirstClassEntity::Function(f) => FirstClassEntity::Function(self.fold_function(f)),
+            FirstClassEntity::Class(c) => FirstClassEntity::Class(self.fold_class(c)),
+        }).collect();
+        *n.get_entities() = entities;
+        n
+    }
+
     fn fold_attribute(&mut self, n: Attribute) -> Attribute { n }
-    fn fold_function(&mut self, n: Function) -> Function { n }
-    fn fold_function_call(&mut self, n: FunctionCall) -> FunctionCall { n }
-    fn fold_param(&mut self, n: Param) -> Param { n }
-    fn visit_operation(&mut self, n: Operation) -> Operation { n }
-    fn visit_expression(&mut self, n: Expression) -> Expression { n }
-    fn fold_qualified_expression(&mut self, n: QualifiedExpression) -> QualifiedExpression { n }
-    fn fold_qualified_expression_part(&mut self, n: QualifiedExpressionPart) -> QualifiedExpressionPart { n }
-    fn fold_statement(&mut self, n: Statement) -> Statement { n }
-    fn fold_closure(&mut self, n: Closure) -> Closure { n }
-    fn fold_variable_declaration(&mut self, n: VariableDeclaration) -> VariableDeclaration { n }
-    fn fold_variable_affectation(&mut self, n: VariableAffectation) -> VariableAffectation { n }
-    fn fold_class(&mut self, n: Class) -> Class { n }
-    fn fold_class_member(&mut self, n: ClassMember) -> ClassMember { n }
-    fn fold_block(&mut self, n: Block) -> Block { n }
-    fn fold_field(&mut self, n: Field) -> Field { n }
+
+    fn fold_function(&mut self, mut n: Function) -> Function {
+        let attributes = n.get_attributes().drain(..).map(|a| self.fold_attribute(a)).collect();
+        *n.get_attributes() = attributes;
+        let type_params = n.get_type_params().drain(..).map(|t| self.fold_type_param(t)).collect();
+        *n.get_type_params() = type_params;
+        let params = n.get_params().drain(..).map(|p| self.fold_param(p)).collect();
+        *n.get_params() = params;
+        let return_type = self.fold_type_ref(n.get_return_type().clone());
+        *n.get_return_type() = return_type;
+        let statements = n.get_statements().drain(..).map(|s| self.fold_statement(s)).collect();
+        *n.get_statements() = statements;
+        n
+    }
+
+    fn fold_function_call(&mut self, mut n: FunctionCall) -> FunctionCall {
+        let param_exprs = n.get_param_exprs().drain(..).map(|e| self.fold_expression(e)).collect();
+        *n.get_param_exprs() = param_exprs;
+        n
+    }
+
+    fn fold_param(&mut self, mut n: Param) -> Param {
+        let param_type = self.fold_type_ref(n.get_type().clone());
+        *n.get_type() = param_type;
+        n
+    }
+
+    fn fold_operation(&mut self, mut n: Operation) -> Operation {
+        let lval = self.fold_expression(n.get_lval().clone());
+        *n.get_lval() = lval;
+        let rval = self.fold_expression(n.get_rval().clone());
+        *n.get_rval() = rval;
+        n
+    }
+
+    fn fold_unary_operation(&mut self, mut n: UnaryOperation) -> UnaryOperation {
+        let expr = self.fold_expression(n.get_expr().clone());
+        *n.get_expr() = expr;
+        n
+    }
+
+    fn fold_expression(&mut self, n: Expression) -> Expression {
+        match n {
+            Expression::Operation(o) => Expression::Operation(self.fold_operation(o)),
+            Expression::Unary(u) => Expression::Unary(self.fold_unary_operation(u)),
+            Expression::Expr(q) => Expression::Expr(self.fold_qualified_expression(q)),
+        }
+    }
+
+    fn fold_qualified_expression(&mut self, mut n: QualifiedExpression) -> QualifiedExpression {
+        let parts = n.get_parts().drain(..).map(|p| self.fold_qualified_expression_part(p)).collect();
+        *n.get_parts() = parts;
+        n
+    }
+
+    fn fold_qualified_expression_part(&mut self, n: QualifiedExpressionPart) -> QualifiedExpressionPart {
+        match n {
+            QualifiedExpressionPart::MethodCall(f) => {
+                QualifiedExpressionPart::MethodCall(self.fold_function_call(f))
+            },
+            QualifiedExpressionPart::Closure(c) => {
+                QualifiedExpressionPart::Closure(self.fold_closure(c))
+            },
+            QualifiedExpressionPart::ParenExpr(e) => {
+                QualifiedExpressionPart::ParenExpr(self.fold_expression(e))
+            },
+            QualifiedExpressionPart::Index(i) => {
+                QualifiedExpressionPart::Index(self.fold_index(i))
+            },
+            QualifiedExpressionPart::Array(a) => {
+                QualifiedExpressionPart::Array(self.fold_array_litteral(a))
+            },
+            QualifiedExpressionPart::Map(m) => {
+                QualifiedExpressionPart::Map(self.fold_map_litteral(m))
+            },
+            QualifiedExpressionPart::Identifier(i) => {
+                QualifiedExpressionPart::Identifier(self.fold_identifier(i))
+            },
+            QualifiedExpressionPart::Integer(i) => {
+                QualifiedExpressionPart::Integer(self.fold_integer(i))
+            },
+            QualifiedExpressionPart::Float(f) => {
+                QualifiedExpressionPart::Float(self.fold_float(f))
+            },
+            QualifiedExpressionPart::StringLitteral(s) => {
+                QualifiedExpressionPart::StringLitteral(self.fold_string_litteral(s))
+            },
+            QualifiedExpressionPart::Char(c) => {
+                QualifiedExpressionPart::Char(self.fold_char(c))
+            },
+            QualifiedExpressionPart::Boolean(b) => {
+                QualifiedExpressionPart::Boolean(self.fold_boolean(b))
+            },
+            QualifiedExpressionPart::Null { span } => QualifiedExpressionPart::Null { span },
+        }
+    }
+
+    fn fold_index(&mut self, mut n: Index) -> Index {
+        let expr = self.fold_expression(n.get_expr().clone());
+        *n.get_expr() = expr;
+        n
+    }
+
+    fn fold_array_litteral(&mut self, mut n: ArrayLitteral) -> ArrayLitteral {
+        let items = n.get_items().drain(..).map(|e| self.fold_expression(e)).collect();
+        *n.get_items() = items;
+        n
+    }
+
+    fn fold_map_litteral(&mut self, mut n: MapLitteral) -> MapLitteral {
+        let entries = n.get_entries().drain(..).map(|(key, value)| {
+            (self.fold_expression(key), self.fold_expression(value))
+        }).collect();
+        *n.get_entries() = entries;
+        n
+    }
+
+    fn fold_statement(&mut self, n: Statement) -> Statement {
+        match n {
+            Statement::ReturnStatement { expr, span } => {
+                Statement::ReturnStatement { expr: expr.map(|e| self.fold_expression(e)), span }
+            },
+            Statement::Declaration(d) => {
+                Statement::Declaration(self.fold_variable_declaration(d))
+            },
+            Statement::Affectation(a) => {
+                Statement::Affectation(self.fold_variable_affectation(a))
+            },
+            Statement::QualifiedExpression(q) => {
+                Statement::QualifiedExpression(self.fold_qualified_expression(q))
+            },
+            Statement::If(i) => Statement::If(self.fold_if_statement(i)),
+            Statement::While(w) => Statement::While(self.fold_while_statement(w)),
+            Statement::For(f) => Statement::For(self.fold_for_statement(f)),
+            Statement::Break { span } => Statement::Break { span },
+            Statement::Continue { span } => Statement::Continue { span },
+        }
+    }
+
+    fn fold_if_statement(&mut self, mut n: IfStatement) -> IfStatement {
+        let condition = self.fold_expression(n.get_condition().clone());
+        *n.get_condition() = condition;
+        let statements = n.get_statements().drain(..).map(|s| self.fold_statement(s)).collect();
+        *n.get_statements() = statements;
+        let else_statements = n.get_else_statements().take().map(|statements| {
+            statements.into_iter().map(|s| self.fold_statement(s)).collect()
+        });
+        *n.get_else_statements() = else_statements;
+        n
+    }
+
+    fn fold_while_statement(&mut self, mut n: WhileStatement) -> WhileStatement {
+        let condition = self.fold_expression(n.get_condition().clone());
+        *n.get_condition() = condition;
+        let statements = n.get_statements().drain(..).map(|s| self.fold_statement(s)).collect();
+        *n.get_statements() = statements;
+        n
+    }
+
+    fn fold_for_statement(&mut self, mut n: ForStatement) -> ForStatement {
+        let init = n.get_init().take().map(|s| Box::new(self.fold_statement(*s)));
+        *n.get_init() = init;
+        let condition = n.get_condition().take().map(|e| self.fold_expression(e));
+        *n.get_condition() = condition;
+        let update = n.get_update().take().map(|s| Box::new(self.fold_statement(*s)));
+        *n.get_update() = update;
+        let statements = n.get_statements().drain(..).map(|s| self.fold_statement(s)).collect();
+        *n.get_statements() = statements;
+        n
+    }
+
+    fn fold_closure(&mut self, mut n: Closure) -> Closure {
+        let params = n.get_params().drain(..).map(|p| self.fold_param(p)).collect();
+        *n.get_params() = params;
+        let return_type = n.get_return_type().take().map(|t| self.fold_type_ref(t));
+        *n.get_return_type() = return_type;
+        let statements = n.get_statements().drain(..).map(|s| self.fold_statement(s)).collect();
+        *n.get_statements() = statements;
+        n
+    }
+
+    fn fold_variable_declaration(&mut self, mut n: VariableDeclaration) -> VariableDeclaration {
+        let var_type = self.fold_type_ref(n.get_type().clone());
+        *n.get_type() = var_type;
+        let value = n.get_value().take().map(|e| self.fold_expression(e));
+        *n.get_value() = value;
+        n
+    }
+
+    fn fold_variable_affectation(&mut self, mut n: VariableAffectation) -> VariableAffectation {
+        let receiver = self.fold_qualified_expression(n.get_receiver().clone());
+        *n.get_receiver() = receiver;
+        let value = self.fold_expression(n.get_value().clone());
+        *n.get_value() = value;
+        n
+    }
+
+    fn fold_class(&mut self, mut n: Class) -> Class {
+        let attributes = n.get_attributes().drain(..).map(|a| self.fold_attribute(a)).collect();
+        *n.get_attributes() = attributes;
+        let type_params = n.get_type_params().drain(..).map(|t| self.fold_type_param(t)).collect();
+        *n.get_type_params() = type_params;
+        let super_class = self.fold_type_ref(n.get_super_class().clone());
+        *n.get_super_class() = super_class;
+        let interfaces = n.get_implemented_interfaces().drain(..).map(|i| self.fold_type_ref(i)).collect();
+        *n.get_implemented_interfaces() = interfaces;
+        let members = n.get_members().drain(..).map(|m| self.fold_class_member(m)).collect();
+        *n.get_members() = members;
+        n
+    }
+
+    fn fold_class_member(&mut self, n: ClassMember) -> ClassMember {
+        match n {
+            ClassMember::Field(f) => ClassMember::Field(self.fold_field(f)),
+            ClassMember::Method(f) => ClassMember::Method(self.fold_function(f)),
+            ClassMember::Block(b) => ClassMember::Block(self.fold_block(b)),
+        }
+    }
+
+    fn fold_block(&mut self, mut n: Block) -> Block {
+        let attributes = n.get_attributes().drain(..).map(|a| self.fold_attribute(a)).collect();
+        *n.get_attributes() = attributes;
+        let members = n.get_members().drain(..).map(|m| self.fold_class_member(m)).collect();
+        *n.get_members() = members;
+        n
+    }
+
+    fn fold_field(&mut self, mut n: Field) -> Field {
+        let attributes = n.get_attributes().drain(..).map(|a| self.fold_attribute(a)).collect();
+        *n.get_attributes() = attributes;
+        let field_type = self.fold_type_ref(n.get_type().clone());
+        *n.get_type() = field_type;
+        n
+    }
+
+    fn fold_type_ref(&mut self, n: TypeRef) -> TypeRef {
+        match n {
+            TypeRef::Named(t) => TypeRef::Named(self.fold_named_type(t)),
+            TypeRef::Closure(t) => TypeRef::Closure(self.fold_closure_type(t)),
+        }
+    }
+
+    fn fold_named_type(&mut self, mut n: NamedType) -> NamedType {
+        let type_args = n.get_type_args().drain(..).map(|t| self.fold_type_ref(t)).collect();
+        *n.get_type_args() = type_args;
+        n
+    }
+
+    fn fold_closure_type(&mut self, mut n: ClosureType) -> ClosureType {
+        let params = n.get_params().drain(..).map(|t| self.fold_type_ref(t)).collect();
+        *n.get_params() = params;
+        let return_type = self.fold_type_ref(n.get_return_type().clone());
+        *n.get_return_type() = return_type;
+        n
+    }
+
+    fn fold_type_param(&mut self, mut n: TypeParam) -> TypeParam {
+        let bounds = n.get_bounds().drain(..).map(|t| self.fold_type_ref(t)).collect();
+        *n.get_bounds() = bounds;
+        n
+    }
+
     fn fold_identifier(&mut self, n: Identifier) -> Identifier { n }
     fn fold_integer(&mut self, n: Integer) -> Integer { n }
+    fn fold_float(&mut self, n: Float) -> Float { n }
     fn fold_string_litteral(&mut self, n: StringLitteral) -> StringLitteral { n }
     fn fold_boolean(&mut self, n: Boolean) -> Boolean { n }
     fn fold_char(&mut self, n: Char) -> Char { n }
@@ -23,9 +23,13 @@ pub trait AstVisitor {
         for attribute in n.get_attributes() {
             self.visit_attribute(attribute);
         }
+        for type_param in n.get_type_params() {
+            self.visit_type_param(type_param);
+        }
         for param in n.get_params() {
             self.visit_param(param);
         }
+        self.visit_type_ref(n.get_return_type());
         for stmt in n.get_statements() {
             self.visit_statement(stmt);
         }
@@ -37,18 +41,27 @@ pub trait AstVisitor {
         }
     }
 
-    fn visit_param(&mut self, n: &mut Param) -> () {}
+    fn visit_param(&mut self, n: &mut Param) -> () {
+        self.visit_type_ref(n.get_type());
+    }
 
     fn visit_operation(&mut self, n: &mut Operation) -> () {
         self.visit_expression(n.get_lval());
         self.visit_expression(n.get_rval());
     }
 
+    fn visit_unary_operation(&mut self, n: &mut UnaryOperation) -> () {
+        self.visit_expression(n.get_expr());
+    }
+
     fn visit_expression(&mut self, n: &mut Expression) -> () {
         match n {
             Expression::Operation(o) => {
                 self.visit_operation(o);
             },
+            Expression::Unary(u) => {
+                self.visit_unary_operation(u);
+            },
             Expression::Expr(q) => {
                 self.visit_qualified_expression(q);
             },
@@ -72,12 +85,24 @@ pub trait AstVisitor {
             QualifiedExpressionPart::ParenExpr(e) => {
                 self.visit_expression(e);
             },
+            QualifiedExpressionPart::Index(i) => {
+                self.visit_index(i);
+            },
+            QualifiedExpressionPart::Array(a) => {
+                self.visit_array_litteral(a);
+            },
+            QualifiedExpressionPart::Map(m) => {
+                self.visit_map_litteral(m);
+            },
             QualifiedExpressionPart::Identifier(i) => {
                 self.visit_identifier(i);
             },
             QualifiedExpressionPart::Integer(i) => {
                 self.visit_integer(i);
             },
+            QualifiedExpressionPart::Float(f) => {
+                self.visit_float(f);
+            },
             QualifiedExpressionPart::StringLitteral(s) => {
                 self.visit_string_litteral(s);
             },
@@ -91,9 +116,26 @@ pub trait AstVisitor {
         }
     }
 
+    fn visit_index(&mut self, n: &mut Index) -> () {
+        self.visit_expression(n.get_expr());
+    }
+
+    fn visit_array_litteral(&mut self, n: &mut ArrayLitteral) -> () {
+        for item in n.get_items() {
+            self.visit_expression(item);
+        }
+    }
+
+    fn visit_map_litteral(&mut self, n: &mut MapLitteral) -> () {
+        for (key, value) in n.get_entries() {
+            self.visit_expression(key);
+            self.visit_expression(value);
+        }
+    }
+
     fn visit_statement(&mut self, n: &mut Statement) -> () {
         match n {
-            Statement::ReturnStatement { expr, pos: _ } => {
+            Statement::ReturnStatement { expr, span: _ } => {
                 match expr {
                     Some(e) => {
                         self.visit_expression(e);
@@ -110,6 +152,50 @@ pub trait AstVisitor {
             Statement::QualifiedExpression(q) => {
                 self.visit_qualified_expression(q);
             },
+            Statement::If(i) => {
+                self.visit_if_statement(i);
+            },
+            Statement::While(w) => {
+                self.visit_while_statement(w);
+            },
+            Statement::For(f) => {
+                self.visit_for_statement(f);
+            },
+            Statement::Break { span: _ } | Statement::Continue { span: _ } => {},
+        }
+    }
+
+    fn visit_if_statement(&mut self, n: &mut IfStatement) -> () {
+        self.visit_expression(n.get_condition());
+        for stmt in n.get_statements() {
+            self.visit_statement(stmt);
+        }
+        if let Some(else_statements) = n.get_else_statements() {
+            for stmt in else_statements {
+                self.visit_statement(stmt);
+            }
+        }
+    }
+
+    fn visit_while_statement(&mut self, n: &mut WhileStatement) -> () {
+        self.visit_expression(n.get_condition());
+        for stmt in n.get_statements() {
+            self.visit_statement(stmt);
+        }
+    }
+
+    fn visit_for_statement(&mut self, n: &mut ForStatement) -> () {
+        if let Some(init) = n.get_init() {
+            self.visit_statement(init);
+        }
+        if let Some(condition) = n.get_condition() {
+            self.visit_expression(condition);
+        }
+        if let Some(update) = n.get_update() {
+            self.visit_statement(update);
+        }
+        for stmt in n.get_statements() {
+            self.visit_statement(stmt);
         }
     }
 
@@ -117,12 +203,16 @@ pub trait AstVisitor {
         for param in n.get_params() {
             self.visit_param(param);
         }
+        if let Some(return_type) = n.get_return_type() {
+            self.visit_type_ref(return_type);
+        }
         for stmt in n.get_statements() {
             self.visit_statement(stmt);
         }
     }
 
     fn visit_variable_declaration(&mut self, n: &mut VariableDeclaration) -> () {
+        self.visit_type_ref(n.get_type());
         match n.get_value() {
             Some(e) => {
                 self.visit_expression(e);
@@ -140,6 +230,13 @@ pub trait AstVisitor {
         for attribute in n.get_attributes() {
             self.visit_attribute(attribute);
         }
+        for type_param in n.get_type_params() {
+            self.visit_type_param(type_param);
+        }
+        self.visit_type_ref(n.get_super_class());
+        for interface in n.get_implemented_interfaces() {
+            self.visit_type_ref(interface);
+        }
         for member in n.get_members() {
             self.visit_class_member(member);
         }
@@ -169,10 +266,42 @@ pub trait AstVisitor {
         for attribute in n.get_attributes() {
             self.visit_attribute(attribute);
         }
+        self.visit_type_ref(n.get_type());
+    }
+
+    fn visit_type_ref(&mut self, n: &mut TypeRef) -> () {
+        match n {
+            TypeRef::Named(t) => {
+                self.visit_named_type(t);
+            },
+            TypeRef::Closure(t) => {
+                self.visit_closure_type(t);
+            }
+        }
+    }
+
+    fn visit_named_type(&mut self, n: &mut NamedType) -> () {
+        for type_arg in n.get_type_args() {
+            self.visit_type_ref(type_arg);
+        }
+    }
+
+    fn visit_closure_type(&mut self, n: &mut ClosureType) -> () {
+        for param in n.get_params() {
+            self.visit_type_ref(param);
+        }
+        self.visit_type_ref(n.get_return_type());
+    }
+
+    fn visit_type_param(&mut self, n: &mut TypeParam) -> () {
+        for bound in n.get_bounds() {
+            self.visit_type_ref(bound);
+        }
     }
 
     fn visit_identifier(&mut self, n: &mut Identifier) -> () {}
     fn visit_integer(&mut self, n: &mut Integer) -> () {}
+    fn visit_float(&mut self, _n: &mut Float) -> () {}
     fn visit_string_litteral(&mut self, n: &mut StringLitteral) -> () {}
     fn visit_boolean(&mut self, n: &mut Boolean) -> () {}
     fn visit_char(&mut self, n: &mut Char) -> () {}
@@ -22,7 +22,7 @@ fn expand_block(b: &mut Block) -> Vec<ClassMember> {
             ClassMember::Field(f) => {
                 new_members.push(ClassMember::Field(
                     Field::new(
-                        f.get_pos(),
+                        f.get_span(),
                         {
                             let mut v = vec![];
                             v.append(f.get_attributes());
@@ -30,14 +30,14 @@ fn expand_block(b: &mut Block) -> Vec<ClassMember> {
                             v
                         },
                         String::from(f.get_name()),
-                        String::from(f.get_type_name()),
+                        f.get_type().clone(),
                     )
                 ));
             },
             ClassMember::Method(m) => {
                 new_members.push(ClassMember::Method(
                     Function::new(
-                        m.get_pos(),
+                        m.get_span(),
                         {
                             let mut v = vec![];
                             v.append(m.get_attributes());
@@ -45,8 +45,9 @@ fn expand_block(b: &mut Block) -> Vec<ClassMember> {
                             v
                         },
                         String::from(m.get_name()),
+                        m.get_type_params().clone(),
                         m.get_params().clone(),
-                        String::from(m.get_return_type()),
+                        m.get_return_type().clone(),
                         m.has_body(),
                         m.get_statements().clone(),
                     )
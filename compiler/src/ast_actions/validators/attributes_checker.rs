@@ -0,0 +1,164 @@
+extern crate lang_parser;
+
+use lang_parser::ast::*;
+use lang_parser::Span;
+use super::super::ast_visitor::AstVisitor;
+use source_file::SourceFile;
+use attributes::{AttributeTarget, find_attribute};
+use errors::diagnostic::{Diagnostic, DiagnosticSink};
+use errors::codes::{UNKNOWN_ATTRIBUTE, MISPLACED_ATTRIBUTE, CONFLICTING_ATTRIBUTES, REPEATED_ATTRIBUTE};
+use errors::classes::class_err::{class_error, class_member_error};
+use errors::functions::function_err::{function_error};
+
+/**
+ * Validates the attributes of every declaration against the registry of
+ * built-in attributes: unknown attributes, attributes put on a declaration
+ * they do not apply to, conflicting and repeated attributes.
+ * Members of a block are checked with the attributes of the block they will
+ * be given by the block expansion.
+ */
+pub fn check_attributes(s: &mut SourceFile, sink: &mut DiagnosticSink) {
+    let mut checker = AttributesChecker::new(sink);
+    checker.visit_file(s.get_ast());
+}
+
+struct AttributesChecker<'a> {
+    sink: &'a mut DiagnosticSink,
+    namespace: String,
+    class: Option<String>,
+    // attributes of the blocks around the current member, outermost first
+    block_attributes: Vec<Attribute>,
+}
+
+impl<'a> AttributesChecker<'a> {
+    pub fn new(sink: &'a mut DiagnosticSink) -> Self {
+        AttributesChecker {
+            sink,
+            namespace: String::from(""),
+            class: None,
+            block_attributes: vec![],
+        }
+    }
+
+    /**
+     * Error about the declaration `name`, a class when there is no current
+     * class, a member of the current class otherwise
+     */
+    fn error(&self, code: &str, msg: &str, name: &str, target: AttributeTarget, span: Span) -> Diagnostic {
+        match (&self.class, target) {
+            (_, AttributeTarget::Class) => class_error(code, msg, &self.namespace, name, span),
+            (Some(class), _) => class_member_error(code, msg, &self.namespace, class, name, span),
+            (None, _) => function_error(code, msg, &self.namespace, name, span),
+        }
+    }
+
+    /**
+     * Checks the attributes of a declaration, `own_count` being the number
+     * of attributes at the end of `attributes` that are written on the
+     * declaration itself, the other ones come from enclosing blocks and
+     * have been checked with the blocks
+     */
+    fn check(&mut self, attributes: &[Attribute], own_count: usize, name: &str,
+            target: AttributeTarget, span: Span) {
+        let inherited_count = attributes.len() - own_count;
+        for (i, attribute) in attributes.iter().enumerate() {
+            let is_inherited = i < inherited_count;
+            let info = match find_attribute(attribute.get_name()) {
+                Some(info) => info,
+                None => {
+                    if !is_inherited {
+                        let msg = format!("unknown attribute `.{}`", attribute.get_name());
+                        let diagnostic = self.error(UNKNOWN_ATTRIBUTE, &msg, name, target, attribute.get_span());
+                        self.sink.push(diagnostic);
+                    }
+                    continue;
+                }
+            };
+
+            // a block attribute that cannot be put on a block is reported
+            // on the block only
+            if !info.applies_to(target) && (!is_inherited || info.applies_to(AttributeTarget::Block)) {
+                let msg = format!("`.{}` cannot be applied to {}", info.get_name(), target.get_name());
+                let mut diagnostic = self.error(MISPLACED_ATTRIBUTE, &msg, name, target, attribute.get_span());
+                if is_inherited {
+                    diagnostic = diagnostic.with_secondary(span, "given to this member by its block");
+                }
+                let targets: Vec<&str> = info.get_targets().iter().map(|t| t.get_name()).collect();
+                let help = format!("`.{}` applies to {}", info.get_name(), targets.join(", "));
+                self.sink.push(diagnostic.with_help(&help));
+            }
+
+            // each pair is reported once, on its second attribute, pairs of
+            // block attributes are reported on the block
+            if is_inherited {
+                continue;
+            }
+            for previous in &attributes[..i] {
+                let code_and_msg = if previous.get_name() == attribute.get_name() && !info.is_repeatable() {
+                    Some((REPEATED_ATTRIBUTE, format!("`.{}` is applied more than once", info.get_name())))
+                } else if info.conflicts_with(previous.get_name()) {
+                    Some((CONFLICTING_ATTRIBUTES, format!("`.{}` and `.{}` cannot be used together",
+                            previous.get_name(), info.get_name())))
+                } else {
+                    None
+                };
+                if let Some((code, msg)) = code_and_msg {
+                    let diagnostic = self.error(code, &msg, name, target, attribute.get_span())
+                            .with_secondary(previous.get_span(), &format!("`.{}` applied here", previous.get_name()));
+                    self.sink.push(diagnostic);
+                }
+            }
+        }
+    }
+
+    fn check_member(&mut self, own_attributes: &[Attribute], name: &str, target: AttributeTarget, span: Span) {
+        let mut attributes = self.block_attributes.clone();
+        attributes.extend_from_slice(own_attributes);
+        self.check(&attributes, own_attributes.len(), name, target, span);
+    }
+}
+
+impl<'a> AstVisitor for AttributesChecker<'a> {
+    fn visit_file(&mut self, n: &mut File) {
+        self.namespace = String::from(n.get_namespace());
+        for entity in n.get_entities() {
+            match entity {
+                FirstClassEntity::Function(f) => {
+                    self.class = None;
+                    let attributes = f.get_attributes().clone();
+                    self.check(&attributes, attributes.len(), f.get_name(), AttributeTarget::Function, f.get_span());
+                },
+                FirstClassEntity::Class(c) => {
+                    self.class = None;
+                    let attributes = c.get_attributes().clone();
+                    self.check(&attributes, attributes.len(), c.get_name(), AttributeTarget::Class, c.get_span());
+                    self.class = Some(String::from(c.get_name()));
+                    self.visit_class(c);
+                },
+            }
+        }
+    }
+
+    fn visit_class_member(&mut self, n: &mut ClassMember) {
+        match n {
+            ClassMember::Field(f) => {
+                let attributes = f.get_attributes().clone();
+                self.check_member(&attributes, f.get_name(), AttributeTarget::Field, f.get_span());
+            },
+            ClassMember::Method(m) => {
+                let attributes = m.get_attributes().clone();
+                self.check_member(&attributes, m.get_name(), AttributeTarget::Method, m.get_span());
+            },
+            ClassMember::Block(b) => {
+                let attributes = b.get_attributes().clone();
+                self.check_member(&attributes, "<block>", AttributeTarget::Block, b.get_span());
+                let block_attributes_count = self.block_attributes.len();
+                self.block_attributes.extend(attributes);
+                for member in b.get_members() {
+                    self.visit_class_member(member);
+                }
+                self.block_attributes.truncate(block_attributes_count);
+            },
+        }
+    }
+}
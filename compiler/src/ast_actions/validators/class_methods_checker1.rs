@@ -1,40 +1,47 @@
 extern crate lang_parser;
 
 use lang_parser::ast::*;
+use lang_parser::Span;
 use super::super::ast_visitor::AstVisitor;
 use source_file::SourceFile;
-use errors::classes::class_err::{class_error, class_member_error};
+use errors::diagnostic::DiagnosticSink;
+use errors::codes::*;
+use errors::classes::class_err::{class_member_error};
 
 /**
  * Validate .native, .interface and .abstract attributes on classes and
- * theirs methods
+ * theirs methods, their mutual exclusivity is checked by the attributes
+ * checker
  */
-pub fn check_methods_body(s: &mut SourceFile) {
-    let mut checker = MethodsAttributesChecker::new(String::from(s.get_path()));
+pub fn check_methods_body(s: &mut SourceFile, sink: &mut DiagnosticSink) {
+    let mut checker = MethodsAttributesChecker::new(sink);
     checker.visit_file(s.get_ast());
 }
 
-struct MethodsAttributesChecker {
+struct MethodsAttributesChecker<'a> {
+    sink: &'a mut DiagnosticSink,
     class: String,
     namespace: String,
-    file: String,
-    is_abstract: bool,
-    is_interface: bool,
+    class_span: Option<Span>,
+    // spans of the class .abstract and .interface attributes
+    abstract_attribute: Option<Span>,
+    interface_attribute: Option<Span>,
 }
 
-impl MethodsAttributesChecker {
-    pub fn new(file: String) -> Self {
+impl<'a> MethodsAttributesChecker<'a> {
+    pub fn new(sink: &'a mut DiagnosticSink) -> Self {
         MethodsAttributesChecker {
+            sink,
             class: String::from(""),
             namespace: String::from(""),
-            file,
-            is_abstract: false,
-            is_interface: false,
+            class_span: None,
+            abstract_attribute: None,
+            interface_attribute: None,
         }
     }
 }
 
-impl AstVisitor for MethodsAttributesChecker {
+impl<'a> AstVisitor for MethodsAttributesChecker<'a> {
     fn visit_file(&mut self, n: &mut File) -> () {
         self.namespace = String::from(n.get_namespace());
         for entity in n.get_entities() {
@@ -49,60 +56,64 @@ impl AstVisitor for MethodsAttributesChecker {
     }
 
     fn visit_class(&mut self, n: &mut Class) -> () {
+        self.class_span = Some(n.get_span());
         for attribute in n.get_attributes() {
             if attribute.get_name() == "abstract" {
-                self.is_abstract = true;
+                self.abstract_attribute = Some(attribute.get_span());
             }
             if attribute.get_name() == "interface" {
-                self.is_interface = true;
+                self.interface_attribute = Some(attribute.get_span());
             }
         }
 
-        if self.is_abstract && self.is_interface {
-            class_error("cannot be abstract and interface at the same time",
-                &self.namespace, &self.class, &self.file, n.get_pos());
-        }
         for member in n.get_members() {
             self.visit_class_member(member);
         }
         // cleanup
-        self.is_interface = false;
-        self.is_abstract = false;
+        self.class_span = None;
+        self.interface_attribute = None;
+        self.abstract_attribute = None;
         self.class = String::from("");
     }
 
     fn visit_function(&mut self, n: &mut Function) -> () {
-        let mut is_native_function = false;
-        let mut is_abstract_function = false;
+        let mut native_attribute: Option<Span> = None;
+        let mut abstract_attribute: Option<Span> = None;
         let name = String::from(n.get_name());
-        let pos = n.get_pos();
+        let span = n.get_span();
+        let is_abstract_class = self.abstract_attribute.is_some();
+        let is_interface = self.interface_attribute.is_some();
         for attribute in n.get_attributes() {
-            if !self.is_abstract && attribute.get_name() == "abstract" {
-                class_member_error("no abstract methods allowed in non-abstract classes",
-                        &self.namespace, &self.class, &name, &self.file, pos);
+            if !is_abstract_class && attribute.get_name() == "abstract" {
+                self.sink.push(class_member_error(ABSTRACT_METHOD_IN_CONCRETE_CLASS,
+                        "no abstract methods allowed in non-abstract classes",
+                        &self.namespace, &self.class, &name, attribute.get_span())
+                            .with_secondary(self.class_span.unwrap(), "the class is not abstract")
+                            .with_help("mark the class .abstract or give the method a body"));
             } else if attribute.get_name() == "native" {
-                is_native_function = true;
+                native_attribute = Some(attribute.get_span());
             } else if attribute.get_name() == "abstract" {
-                is_abstract_function = true;
+                abstract_attribute = Some(attribute.get_span());
             }
         }
-        if is_abstract_function && is_native_function {
-            class_member_error("cannot be abstract and native at the same time",
-                &self.namespace, &self.class, &name, &self.file, pos);
-        }
         let body = n.has_body();
-        if (!self.is_abstract && !self.is_interface && !is_native_function) && !body {
-            class_member_error("bodyless methods in normal classes are only allowed if natives",
-                    &self.namespace, &self.class, &name, &self.file, pos);
-        } else if self.is_interface && body {
-            class_member_error("interfaces can only have bodyless methods", &self.namespace,
-                    &self.class, &name, &self.file, pos);
-        } else if is_native_function && body {
-            class_member_error("native methods cannot have a body", &self.namespace,
-                    &self.class, &name, &self.file, pos);
-        } else if is_abstract_function && body {
-            class_member_error("abstract methods cannot have a body", &self.namespace,
-                    &self.class, &name, &self.file, pos);
+        if (!is_abstract_class && !is_interface && native_attribute.is_none()) && !body {
+            self.sink.push(class_member_error(BODYLESS_METHOD,
+                    "bodyless methods in normal classes are only allowed if natives",
+                    &self.namespace, &self.class, &name, span)
+                        .with_help("give the method a body or mark it .native"));
+        } else if let (Some(interface_span), true) = (self.interface_attribute, body) {
+            self.sink.push(class_member_error(INTERFACE_METHOD_BODY, "interfaces can only have bodyless methods",
+                    &self.namespace, &self.class, &name, span)
+                        .with_secondary(interface_span, "the class is declared as an interface here"));
+        } else if let (Some(native_span), true) = (native_attribute, body) {
+            self.sink.push(class_member_error(NATIVE_METHOD_BODY, "native methods cannot have a body",
+                    &self.namespace, &self.class, &name, span)
+                        .with_secondary(native_span, "declared native here"));
+        } else if let (Some(abstract_span), true) = (abstract_attribute, body) {
+            self.sink.push(class_member_error(ABSTRACT_METHOD_BODY, "abstract methods cannot have a body",
+                    &self.namespace, &self.class, &name, span)
+                        .with_secondary(abstract_span, "declared abstract here"));
         }
     }
 }
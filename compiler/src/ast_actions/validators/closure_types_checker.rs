@@ -0,0 +1,216 @@
+extern crate lang_parser;
+
+use lang_parser::ast::*;
+use lang_parser::Span;
+use super::super::ast_visitor::AstVisitor;
+use source_file::SourceFile;
+use super::declared_types::DeclaredTypes;
+use symbol_table::GlobalSymbolTable;
+use import_resolution::{FileScopes, FileScope};
+use errors::diagnostic::DiagnosticSink;
+use errors::codes::CLOSURE_TYPE_MISMATCH;
+use errors::classes::class_err::{class_member_error};
+use errors::functions::function_err::{function_error};
+
+/**
+ * Checks that closure litterals are compatible with the closure type they
+ * are given to: declared variable type, assigned variable or field type,
+ * return type and parameter type of the called function or method
+ */
+pub fn check_closure_types(s: &mut SourceFile, gst: &GlobalSymbolTable, scopes: &FileScopes, sink: &mut DiagnosticSink) {
+    let scope = scopes.get(&s.get_file_id()).expect("imports are resolved before the closure types checks");
+    let mut checker = ClosureTypesChecker::new(gst, scope, sink);
+    checker.visit_file(s.get_ast());
+}
+
+struct ClosureTypesChecker<'a> {
+    sink: &'a mut DiagnosticSink,
+    function: String,
+    types: DeclaredTypes<'a>,
+    // return types of the function and its closures, innermost closure last
+    return_types: Vec<Option<TypeRef>>,
+}
+
+impl<'a> ClosureTypesChecker<'a> {
+    pub fn new(gst: &'a GlobalSymbolTable, scope: &'a FileScope, sink: &'a mut DiagnosticSink) -> Self {
+        ClosureTypesChecker {
+            sink,
+            function: String::from(""),
+            types: DeclaredTypes::new(gst, scope),
+            return_types: vec![],
+        }
+    }
+
+    fn error(&mut self, code: &str, msg: &str, span: Span) {
+        let namespace = self.types.get_namespace();
+        let diagnostic = match self.types.get_class() {
+            Some(class) => class_member_error(code, msg, namespace, class, &self.function, span),
+            None => function_error(code, msg, namespace, &self.function, span),
+        };
+        self.sink.push(diagnostic);
+    }
+
+    /**
+     * Reports `closure` if it is given where a closure type it does not
+     * match is expected
+     */
+    fn check_closure(&mut self, closure: &mut Closure, expected: &TypeRef) {
+        let mut expected_type = match expected {
+            TypeRef::Closure(t) => t.clone(),
+            // a plain `Closure` accepts any closure, other types are
+            // left to the type checker
+            TypeRef::Named(_) => return,
+        };
+        let span = closure.get_span();
+        match closure.get_return_type().clone() {
+            Some(return_type) => {
+                let params = closure.get_params().iter_mut().map(|p| p.get_type().clone()).collect();
+                let closure_type = TypeRef::Closure(ClosureType::new(span, params, return_type));
+                if !closure_type.is_same_type(expected) {
+                    self.error(CLOSURE_TYPE_MISMATCH, &format!("closure of type {} is not compatible with {}", closure_type, expected), span);
+                }
+            },
+            None => {
+                // a closure without signature takes no parameter
+                if !expected_type.get_params().is_empty() {
+                    self.error(CLOSURE_TYPE_MISMATCH, &format!("closure without parameters is not compatible with {}", expected), span);
+                }
+            }
+        }
+    }
+}
+
+/**
+ * The closure litteral making up the whole expression, if any
+ */
+fn closure_litteral(e: &mut Expression) -> Option<&mut Closure> {
+    match e {
+        Expression::Expr(q) => match q.get_parts().as_mut_slice() {
+            [QualifiedExpressionPart::Closure(c)] => Some(c),
+            _ => None
+        },
+        _ => None
+    }
+}
+
+impl<'a> AstVisitor for ClosureTypesChecker<'a> {
+    fn visit_file(&mut self, n: &mut File) {
+        self.types.set_namespace(n.get_namespace());
+        for entity in n.get_entities() {
+            match entity {
+                FirstClassEntity::Function(f) => {
+                    self.types.set_class(None);
+                    self.visit_function(f);
+                },
+                FirstClassEntity::Class(c) => {
+                    self.types.set_class(Some(c.get_name()));
+                    self.visit_class(c);
+                },
+            }
+        }
+    }
+
+    fn visit_function(&mut self, n: &mut Function) {
+        self.function = String::from(n.get_name());
+        self.types.push_scope();
+        self.return_types.push(Some(n.get_return_type().clone()));
+
+        for param in n.get_params() {
+            self.visit_param(param);
+        }
+        for stmt in n.get_statements() {
+            self.visit_statement(stmt);
+        }
+
+        self.return_types.pop();
+        self.types.pop_scope();
+    }
+
+    fn visit_closure(&mut self, n: &mut Closure) {
+        self.types.push_scope();
+        self.return_types.push(n.get_return_type().clone());
+
+        for param in n.get_params() {
+            self.visit_param(param);
+        }
+        for stmt in n.get_statements() {
+            self.visit_statement(stmt);
+        }
+
+        self.return_types.pop();
+        self.types.pop_scope();
+    }
+
+    fn visit_param(&mut self, n: &mut Param) {
+        let param_type = n.get_type().clone();
+        self.types.declare(n.get_name(), param_type);
+    }
+
+    fn visit_statement(&mut self, n: &mut Statement) {
+        match n {
+            Statement::ReturnStatement { expr: Some(e), span: _ } => {
+                if let Some(Some(return_type)) = self.return_types.last().cloned() {
+                    if let Some(closure) = closure_litteral(e) {
+                        self.check_closure(closure, &return_type);
+                    }
+                }
+                self.visit_expression(e);
+            },
+            Statement::Declaration(d) => {
+                self.visit_variable_declaration(d);
+            },
+            Statement::Affectation(a) => {
+                self.visit_variable_affectation(a);
+            },
+            Statement::QualifiedExpression(q) => {
+                self.visit_qualified_expression(q);
+            },
+            Statement::If(i) => {
+                self.visit_if_statement(i);
+            },
+            Statement::While(w) => {
+                self.visit_while_statement(w);
+            },
+            Statement::For(f) => {
+                self.visit_for_statement(f);
+            },
+            _ => {}
+        }
+    }
+
+    fn visit_variable_declaration(&mut self, n: &mut VariableDeclaration) {
+        let var_type = n.get_type().clone();
+        if let Some(e) = n.get_value() {
+            if let Some(closure) = closure_litteral(e) {
+                self.check_closure(closure, &var_type);
+            }
+            self.visit_expression(e);
+        }
+        self.types.declare(n.get_name(), var_type);
+    }
+
+    fn visit_variable_affectation(&mut self, n: &mut VariableAffectation) {
+        if let Some(receiver_type) = self.types.parts_type(n.get_receiver().get_parts()) {
+            if let Some(closure) = closure_litteral(n.get_value()) {
+                self.check_closure(closure, &receiver_type);
+            }
+        }
+        self.visit_expression(n.get_value());
+    }
+
+    fn visit_qualified_expression(&mut self, n: &mut QualifiedExpression) {
+        let parts = n.get_parts();
+        for index in 0..parts.len() {
+            if let Some(param_types) = self.types.called_params(parts, index) {
+                if let QualifiedExpressionPart::MethodCall(call) = &mut parts[index] {
+                    for (arg, param_type) in call.get_param_exprs().iter_mut().zip(param_types.iter()) {
+                        if let Some(closure) = closure_litteral(arg) {
+                            self.check_closure(closure, param_type);
+                        }
+                    }
+                }
+            }
+            self.visit_qualified_expression_part(&mut parts[index]);
+        }
+    }
+}
@@ -0,0 +1,249 @@
+extern crate lang_parser;
+
+use lang_parser::ast::*;
+use lang_parser::Span;
+use symbol_table::{GlobalSymbolTable, NSTEntry, ClassSymbolTable, CSTEntry, get_overloads};
+use import_resolution::{FileScope, ScopeLookup};
+use std::collections::HashMap;
+
+/**
+ * Declared types of the params, variables and fields visible from the code
+ * being checked, used by the validators that need to know what a
+ * qualified expression refers to
+ */
+pub struct DeclaredTypes<'a> {
+    gst: &'a GlobalSymbolTable,
+    // names brought by the imports of the file being checked
+    scope: &'a FileScope,
+    namespace: String,
+    class: Option<String>,
+    // declared types of the params and variables, innermost closure last
+    scopes: Vec<HashMap<String, TypeRef>>,
+}
+
+impl<'a> DeclaredTypes<'a> {
+    pub fn new(gst: &'a GlobalSymbolTable, scope: &'a FileScope) -> Self {
+        DeclaredTypes {
+            gst,
+            scope,
+            namespace: String::from(""),
+            class: None,
+            scopes: vec![],
+        }
+    }
+
+    pub fn get_namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    pub fn set_namespace(&mut self, namespace: &str) {
+        self.namespace = String::from(namespace);
+    }
+
+    /**
+     * None inside freestanding functions
+     */
+    pub fn get_class(&self) -> Option<&str> {
+        self.class.as_deref()
+    }
+
+    pub fn set_class(&mut self, class: Option<&str>) {
+        self.class = class.map(String::from);
+    }
+
+    pub fn push_scope(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+
+    pub fn pop_scope(&mut self) {
+        self.scopes.pop();
+    }
+
+    pub fn declare(&mut self, name: &str, var_type: TypeRef) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(String::from(name), var_type);
+        }
+    }
+
+    pub fn variable_type(&self, name: &str) -> Option<TypeRef> {
+        self.scopes.iter().rev().filter_map(|scope| scope.get(name)).next().cloned()
+    }
+
+    pub fn field_type(&self, name: &str) -> Option<TypeRef> {
+        match self.current_class_table()?.get(name) {
+            Some(CSTEntry::Field(field_type, _)) => Some(field_type.clone()),
+            _ => None
+        }
+    }
+
+    pub fn current_class_table(&self) -> Option<&'a ClassSymbolTable> {
+        self.class_table(self.class.as_ref()?)
+    }
+
+    /**
+     * Namespace and name of the class or function an unqualified name
+     * refers to in the file being checked: one of its namespace or an
+     * imported one
+     */
+    pub fn resolve(&self, name: &str) -> Option<(String, String)> {
+        match self.scope.resolve(name) {
+            ScopeLookup::Found(namespace, name) => Some((namespace, name)),
+            _ => None
+        }
+    }
+
+    fn nst_entry(&self, name: &str) -> Option<&'a NSTEntry> {
+        let (namespace, name) = self.resolve(name)?;
+        self.gst.get(&namespace)?.get(&name)
+    }
+
+    /**
+     * Symbol table of a class visible from the file being checked
+     */
+    pub fn class_table(&self, class: &str) -> Option<&'a ClassSymbolTable> {
+        match self.nst_entry(class)? {
+            NSTEntry::Class(_, _, _, _, cst) => Some(cst),
+            _ => None
+        }
+    }
+
+    /**
+     * Declared type of a field, or return type of a method, of a class given
+     * by its qualified name, the member being known by its declaration
+     */
+    pub fn member_type(&self, class: &str, declaration: Span) -> Option<TypeRef> {
+        let (namespace, name) = class.rsplit_once('.')?;
+        let cst = match self.gst.get(namespace)?.get(name) {
+            Some(NSTEntry::Class(_, _, _, _, cst)) => cst,
+            _ => return None
+        };
+        cst.values().find_map(|entry| match entry {
+            CSTEntry::Field(member_type, span) | CSTEntry::Method(_, _, member_type, span, _) if *span == declaration => {
+                Some(member_type.clone())
+            },
+            _ => None
+        })
+    }
+
+    /**
+     * Type params, superclass, implemented interfaces and symbol table of a
+     * class visible from the file being checked
+     */
+    pub fn class_declaration(&self, class: &str)
+            -> Option<(&'a Vec<TypeParam>, &'a TypeRef, &'a Vec<TypeRef>, &'a ClassSymbolTable)> {
+        let (namespace, class) = self.resolve(class)?;
+        self.namespace_class_declaration(&namespace, &class)
+    }
+
+    /**
+     * Same as `class_declaration` for a class of `namespace`, the superclass
+     * of a class being written in the namespace of the class
+     */
+    pub fn namespace_class_declaration(&self, namespace: &str, class: &str)
+            -> Option<(&'a Vec<TypeParam>, &'a TypeRef, &'a Vec<TypeRef>, &'a ClassSymbolTable)> {
+        match self.gst.get(namespace)?.get(class) {
+            Some(NSTEntry::Class(type_params, super_class, interfaces, _, cst)) => {
+                Some((type_params, super_class, interfaces, cst))
+            },
+            _ => None
+        }
+    }
+
+    /**
+     * Type params, param types and return type of a freestanding function
+     * visible from the file being checked
+     */
+    pub fn function_declaration(&self, function: &str) -> Option<(&'a Vec<TypeParam>, &'a Vec<TypeRef>, &'a TypeRef)> {
+        match self.nst_entry(function)? {
+            NSTEntry::Fun(type_params, param_types, return_type, _, _) => Some((type_params, param_types, return_type)),
+            _ => None
+        }
+    }
+
+    /**
+     * Declared type of an expression made of identifiers only: `a`, `this`,
+     * `this.a`, `self.a`
+     */
+    pub fn parts_type(&self, parts: &[QualifiedExpressionPart]) -> Option<TypeRef> {
+        let mut names: Vec<&str> = vec![];
+        for part in parts {
+            match part {
+                QualifiedExpressionPart::Identifier(i) => names.push(i.get_name()),
+                _ => return None
+            }
+        }
+        match names.as_slice() {
+            [this] if *this == "this" || *this == "self" => {
+                let class = String::from(self.get_class()?);
+                Some(TypeRef::Named(NamedType::new(parts[0].get_span(), class, vec![])))
+            },
+            [name] => self.variable_type(name),
+            [this, name] if *this == "this" || *this == "self" => self.field_type(name),
+            _ => None
+        }
+    }
+
+    /**
+     * Parameter types of the function or method called by the part at
+     * `index` of a qualified expression, when it can be known. A method is
+     * known when it is the only overload taking that many arguments.
+     */
+    pub fn called_params(&self, parts: &mut [QualifiedExpressionPart], index: usize) -> Option<Vec<TypeRef>> {
+        let (name, args_count) = match &mut parts[index] {
+            QualifiedExpressionPart::MethodCall(call) => (String::from(call.get_name()), call.get_param_exprs().len()),
+            _ => return None
+        };
+        let name = name.as_str();
+        if index == 0 {
+            return self.function_declaration(name).map(|(_, param_types, _)| param_types.clone());
+        }
+        match &parts[index - 1] {
+            QualifiedExpressionPart::Identifier(i) if index == 1
+                    && (i.get_name() == "this" || i.get_name() == "self") => {
+                let candidates: Vec<&Vec<TypeRef>> = get_overloads(self.current_class_table()?, name).into_iter()
+                        .filter_map(|(_, entry)| match entry {
+                            CSTEntry::Method(_, param_types, _, _, _) if param_types.len() == args_count => Some(param_types),
+                            _ => None
+                        })
+                        .collect();
+                match candidates.as_slice() {
+                    [param_types] => Some((*param_types).clone()),
+                    _ => None
+                }
+            },
+            _ => None
+        }
+    }
+
+    /**
+     * Whether the class or one of its superclasses declares the method. The
+     * class is resolved through the imports of the file, its superclasses in
+     * the namespace of the class extending them. None when the answer cannot
+     * be known because the hierarchy leaves the compiled namespaces
+     */
+    pub fn class_has_method(&self, class: &str, method: &str) -> Option<bool> {
+        let (namespace, mut class) = self.resolve(class)?;
+        let nst = self.gst.get(&namespace)?;
+        // guards against inheritance cycles
+        let mut visited: Vec<String> = vec![];
+        loop {
+            let (super_class, cst) = match nst.get(&class) {
+                Some(NSTEntry::Class(_, super_class, _, _, cst)) => (super_class, cst),
+                _ => return None
+            };
+            if !get_overloads(cst, method).is_empty() {
+                return Some(true);
+            }
+            visited.push(class);
+            class = match super_class {
+                TypeRef::Named(t) if t.get_name() == "Object" || t.get_name() == "Interface" => {
+                    return Some(false);
+                },
+                TypeRef::Named(t) if !visited.iter().any(|c| c == t.get_name()) => {
+                    String::from(t.get_name())
+                },
+                _ => return None
+            };
+        }
+    }
+}
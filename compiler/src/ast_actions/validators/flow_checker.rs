@@ -0,0 +1,491 @@
+extern crate lang_parser;
+
+use lang_parser::ast::*;
+use lang_parser::Span;
+use super::super::ast_visitor::AstVisitor;
+use source_file::SourceFile;
+use symbol_table::{GlobalSymbolTable, FunctionSymbolTable, Capture, CaptureMode};
+use closure_captures::function_table;
+use errors::diagnostic::{Diagnostic, DiagnosticSink};
+use errors::codes::{MISSING_RETURN_VALUE, MISSING_RETURN, VOID_RETURN_VALUE, POSSIBLY_UNASSIGNED, UNREACHABLE_CODE,
+        JUMP_OUTSIDE_LOOP};
+use errors::classes::class_err::{class_member_error, class_member_warning};
+use errors::functions::function_err::{function_error, function_warning};
+use std::collections::{HashMap, HashSet};
+
+/**
+ * Follows the control flow of function and closure bodies to report the
+ * bodies that may end without returning a value, returns that do not match
+ * the Void-ness of the return type, reads of variables that may not be
+ * assigned yet, closures capturing variables that may not be assigned yet,
+ * statements that can never run and `break` or `continue` outside of a loop
+ */
+pub fn check_flow(s: &mut SourceFile, gst: &GlobalSymbolTable, sink: &mut DiagnosticSink) {
+    let mut checker = FlowChecker::new(gst, sink);
+    checker.visit_file(s.get_ast());
+}
+
+/**
+ * What is known at a point of a body: whether it can be reached, and the
+ * declarations without a value that may still be unassigned there
+ */
+#[derive(Clone)]
+struct FlowState {
+    reachable: bool,
+    unassigned: HashSet<Span>,
+}
+
+impl FlowState {
+    fn reachable() -> Self {
+        FlowState {
+            reachable: true,
+            unassigned: HashSet::new(),
+        }
+    }
+
+    fn unreachable() -> Self {
+        FlowState {
+            reachable: false,
+            unassigned: HashSet::new(),
+        }
+    }
+
+    /**
+     * State where the paths leading to `self` and `other` join, an
+     * unreachable path brings nothing to the join
+     */
+    fn join(mut self, other: FlowState) -> Self {
+        if !other.reachable {
+            return self;
+        }
+        if !self.reachable {
+            return other;
+        }
+        self.unassigned.extend(other.unassigned);
+        self
+    }
+}
+
+/**
+ * States of the paths leaving an enclosing loop through `break` and going
+ * back to its condition through `continue`
+ */
+struct LoopExits {
+    breaks: FlowState,
+    continues: FlowState,
+}
+
+struct FlowChecker<'a> {
+    gst: &'a GlobalSymbolTable,
+    sink: &'a mut DiagnosticSink,
+    namespace: String,
+    class: Option<String>,
+    function: String,
+    // symbol table of the function being checked
+    fst: Option<&'a FunctionSymbolTable>,
+    state: FlowState,
+    // declarations of the params and variables, innermost scope last
+    scopes: Vec<HashMap<String, Span>>,
+    // exits of the loops of the body being checked, innermost loop last
+    loops: Vec<LoopExits>,
+    // return types of the function and its closures, innermost closure last,
+    // None for closures without signature
+    return_types: Vec<Option<TypeRef>>,
+}
+
+impl<'a> FlowChecker<'a> {
+    pub fn new(gst: &'a GlobalSymbolTable, sink: &'a mut DiagnosticSink) -> Self {
+        FlowChecker {
+            gst,
+            sink,
+            namespace: String::from(""),
+            class: None,
+            function: String::from(""),
+            fst: None,
+            state: FlowState::reachable(),
+            scopes: vec![],
+            loops: vec![],
+            return_types: vec![],
+        }
+    }
+
+    fn error(&self, code: &str, msg: &str, span: Span) -> Diagnostic {
+        match &self.class {
+            Some(class) => class_member_error(code, msg, &self.namespace, class, &self.function, span),
+            None => function_error(code, msg, &self.namespace, &self.function, span),
+        }
+    }
+
+    fn warning(&self, code: &str, msg: &str, span: Span) -> Diagnostic {
+        match &self.class {
+            Some(class) => class_member_warning(code, msg, &self.namespace, class, &self.function, span),
+            None => function_warning(code, msg, &self.namespace, &self.function, span),
+        }
+    }
+
+    fn declare(&mut self, name: &str, declaration: Span) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(String::from(name), declaration);
+        }
+    }
+
+    fn lookup(&self, name: &str) -> Option<Span> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name).cloned())
+    }
+
+    /**
+     * Reports the read of `name` at `span` if it refers to a variable that
+     * may not be assigned yet
+     */
+    fn read(&mut self, name: &str, span: Span) {
+        if let Some(declaration) = self.lookup(name) {
+            if self.state.reachable && self.state.unassigned.contains(&declaration) {
+                let diagnostic = self.error(POSSIBLY_UNASSIGNED, &format!("`{}` may be read before being assigned", name), span)
+                        .with_secondary(declaration, "declared here without a value");
+                self.sink.push(diagnostic);
+            }
+        }
+    }
+
+    fn assign(&mut self, name: &str) {
+        if let Some(declaration) = self.lookup(name) {
+            self.state.unassigned.remove(&declaration);
+        }
+    }
+
+    /**
+     * Checks the statements of a block, in a scope of their own. The first
+     * statement that cannot be reached is reported and the following ones
+     * are skipped
+     */
+    fn check_block(&mut self, statements: &mut Vec<Statement>) {
+        self.scopes.push(HashMap::new());
+        let mut previous: Option<Span> = None;
+        for stmt in statements {
+            if !self.state.reachable {
+                let mut diagnostic = self.warning(UNREACHABLE_CODE, "unreachable statement", stmt.get_span());
+                if let Some(previous) = previous {
+                    diagnostic = diagnostic.with_secondary(previous, "any code following this statement is unreachable");
+                }
+                self.sink.push(diagnostic);
+                break;
+            }
+            self.visit_statement(stmt);
+            previous = Some(stmt.get_span());
+        }
+        self.scopes.pop();
+    }
+
+    /**
+     * What the closure declared at `span` captures, None when the symbol
+     * table of the function is not known
+     */
+    fn captures(&self, span: Span) -> Option<&'a [Capture]> {
+        let fst = self.fst?;
+        Some(fst.get_captures(fst.scope_at(span.get_start_byte())))
+    }
+
+    /**
+     * Reports the variables a closure reads that may not be assigned when
+     * it is created. Gives the unassigned variables its body starts with:
+     * the ones it assigns, they may be read in the body before that
+     */
+    fn check_captures(&mut self, captures: &[Capture], span: Span) -> HashSet<Span> {
+        let mut unassigned: HashSet<Span> = HashSet::new();
+        for capture in captures {
+            let declaration = capture.get_declaration();
+            if !self.state.unassigned.contains(&declaration) {
+                continue;
+            }
+            match capture.get_mode() {
+                CaptureMode::Read if self.state.reachable => {
+                    let msg = format!("closure captures `{}` before it is assigned", capture.get_name());
+                    let diagnostic = self.error(POSSIBLY_UNASSIGNED, &msg, span)
+                            .with_secondary(declaration, "declared here without a value");
+                    self.sink.push(diagnostic);
+                },
+                CaptureMode::Read => {},
+                CaptureMode::Mutate => {
+                    unassigned.insert(declaration);
+                },
+            }
+        }
+        unassigned
+    }
+
+    /**
+     * Checks a function or closure body, reports it if its end can be
+     * reached while a value is expected. The params are assigned and the
+     * variables of the enclosing bodies are assigned unless `unassigned`
+     */
+    fn check_body(&mut self, params: Vec<(String, Span)>, statements: &mut Vec<Statement>, return_type: Option<TypeRef>,
+            unassigned: HashSet<Span>) {
+        let state = FlowState {
+            reachable: true,
+            unassigned,
+        };
+        let outer_state = std::mem::replace(&mut self.state, state);
+        let outer_loops = std::mem::take(&mut self.loops);
+        self.scopes.push(HashMap::new());
+        for (name, declaration) in params {
+            self.declare(&name, declaration);
+        }
+        self.return_types.push(return_type.clone());
+
+        self.check_block(statements);
+        if let Some(return_type) = return_type {
+            if self.state.reachable && !is_void(&return_type) {
+                let msg = format!("the body may end without returning a value, expected {}", return_type);
+                let diagnostic = self.error(MISSING_RETURN, &msg, return_type.get_span());
+                self.sink.push(diagnostic);
+            }
+        }
+
+        self.return_types.pop();
+        self.scopes.pop();
+        self.loops = outer_loops;
+        self.state = outer_state;
+    }
+
+    fn check_return(&mut self, expr: &mut Option<Expression>, span: Span) {
+        if let Some(e) = expr {
+            self.visit_expression(e);
+        }
+        if let Some(Some(return_type)) = self.return_types.last().cloned() {
+            match expr {
+                Some(e) if is_void(&return_type) => {
+                    let diagnostic = self.error(VOID_RETURN_VALUE, "cannot return a value from a Void body", e.get_span())
+                            .with_secondary(return_type.get_span(), "return type declared here");
+                    self.sink.push(diagnostic);
+                },
+                None if !is_void(&return_type) => {
+                    let msg = format!("missing return value, expected {}", return_type);
+                    let diagnostic = self.error(MISSING_RETURN_VALUE, &msg, span)
+                            .with_secondary(return_type.get_span(), "return type declared here");
+                    self.sink.push(diagnostic);
+                },
+                _ => {}
+            }
+        }
+        self.state.reachable = false;
+    }
+
+    /**
+     * Reports a `break` or `continue` that is not in a loop of the body being
+     * checked, the code following it is still checked as reachable
+     */
+    fn jump_outside_loop(&mut self, keyword: &str, span: Span) {
+        let diagnostic = self.error(JUMP_OUTSIDE_LOOP, &format!("`{}` outside of a loop", keyword), span);
+        self.sink.push(diagnostic);
+    }
+
+    /**
+     * Checks a loop body starting from `before`, the state in which the
+     * condition is first evaluated. Assignments only ever remove variables
+     * from the unassigned ones, so going back to the condition does not
+     * change that state. Gives the state at the end of the body joined with
+     * the `continue` ones, and the `break` ones
+     */
+    fn check_loop_body(&mut self, statements: &mut Vec<Statement>) -> (FlowState, FlowState) {
+        self.loops.push(LoopExits {
+            breaks: FlowState::unreachable(),
+            continues: FlowState::unreachable(),
+        });
+        self.check_block(statements);
+        let exits = self.loops.pop().unwrap();
+        let end = std::mem::replace(&mut self.state, FlowState::unreachable());
+
+        (end.join(exits.continues), exits.breaks)
+    }
+}
+
+fn declared_params(params: &[Param]) -> Vec<(String, Span)> {
+    params.iter().map(|p| (String::from(p.get_name()), p.get_span())).collect()
+}
+
+fn is_void(t: &TypeRef) -> bool {
+    match t {
+        TypeRef::Named(n) => n.get_name() == "Void",
+        TypeRef::Closure(_) => false,
+    }
+}
+
+/**
+ * Whether the loop condition is the `true` litteral, the loop is then only
+ * left through `break` or `return`
+ */
+pub fn is_always_true(condition: &mut Expression) -> bool {
+    match condition {
+        Expression::Expr(q) => match q.get_parts().as_slice() {
+            [QualifiedExpressionPart::Boolean(b)] => *b.get_value(),
+            _ => false
+        },
+        _ => false
+    }
+}
+
+impl<'a> AstVisitor for FlowChecker<'a> {
+    fn visit_file(&mut self, n: &mut File) {
+        self.namespace = String::from(n.get_namespace());
+        for entity in n.get_entities() {
+            match entity {
+                FirstClassEntity::Function(f) => {
+                    self.class = None;
+                    self.visit_function(f);
+                },
+                FirstClassEntity::Class(c) => {
+                    self.class = Some(String::from(c.get_name()));
+                    self.visit_class(c);
+                },
+            }
+        }
+    }
+
+    fn visit_function(&mut self, n: &mut Function) {
+        // abstract, native and interface methods have no body to follow
+        if !n.has_body() {
+            return;
+        }
+        self.function = String::from(n.get_name());
+        self.fst = function_table(self.gst, &self.namespace, self.class.as_deref(), n.get_span());
+        self.state = FlowState::reachable();
+        let return_type = n.get_return_type().clone();
+        let params = declared_params(n.get_params());
+        self.check_body(params, n.get_statements(), Some(return_type), HashSet::new());
+        self.fst = None;
+    }
+
+    fn visit_closure(&mut self, n: &mut Closure) {
+        let unassigned = match self.captures(n.get_span()) {
+            Some(captures) => self.check_captures(captures, n.get_span()),
+            None => self.state.unassigned.clone()
+        };
+        let return_type = n.get_return_type().clone();
+        let params = declared_params(n.get_params());
+        self.check_body(params, n.get_statements(), return_type, unassigned);
+    }
+
+    fn visit_statement(&mut self, n: &mut Statement) {
+        match n {
+            Statement::ReturnStatement { expr, span } => {
+                self.check_return(expr, *span);
+            },
+            Statement::Declaration(d) => {
+                self.visit_variable_declaration(d);
+            },
+            Statement::Affectation(a) => {
+                self.visit_variable_affectation(a);
+            },
+            Statement::QualifiedExpression(q) => {
+                self.visit_qualified_expression(q);
+            },
+            Statement::If(i) => {
+                self.visit_if_statement(i);
+            },
+            Statement::While(w) => {
+                self.visit_while_statement(w);
+            },
+            Statement::For(f) => {
+                self.visit_for_statement(f);
+            },
+            Statement::Break { span } => {
+                if self.loops.is_empty() {
+                    self.jump_outside_loop("break", *span);
+                    return;
+                }
+                let state = std::mem::replace(&mut self.state, FlowState::unreachable());
+                let exits = self.loops.last_mut().unwrap();
+                exits.breaks = std::mem::replace(&mut exits.breaks, FlowState::unreachable()).join(state);
+            },
+            Statement::Continue { span } => {
+                if self.loops.is_empty() {
+                    self.jump_outside_loop("continue", *span);
+                    return;
+                }
+                let state = std::mem::replace(&mut self.state, FlowState::unreachable());
+                let exits = self.loops.last_mut().unwrap();
+                exits.continues = std::mem::replace(&mut exits.continues, FlowState::unreachable()).join(state);
+            },
+        }
+    }
+
+    fn visit_if_statement(&mut self, n: &mut IfStatement) {
+        self.visit_expression(n.get_condition());
+        let before = self.state.clone();
+        self.check_block(n.get_statements());
+        let then_state = std::mem::replace(&mut self.state, before);
+        if let Some(else_statements) = n.get_else_statements() {
+            self.check_block(else_statements);
+        }
+        let else_state = std::mem::replace(&mut self.state, FlowState::unreachable());
+        self.state = then_state.join(else_state);
+    }
+
+    fn visit_while_statement(&mut self, n: &mut WhileStatement) {
+        self.visit_expression(n.get_condition());
+        let before = self.state.clone();
+        let (_, breaks) = self.check_loop_body(n.get_statements());
+        self.state = if is_always_true(n.get_condition()) { breaks } else { before.join(breaks) };
+    }
+
+    fn visit_for_statement(&mut self, n: &mut ForStatement) {
+        self.scopes.push(HashMap::new());
+        if let Some(init) = n.get_init() {
+            self.visit_statement(init);
+        }
+        let is_infinite = match n.get_condition() {
+            Some(condition) => {
+                self.visit_expression(condition);
+                is_always_true(condition)
+            },
+            None => true
+        };
+        let before = self.state.clone();
+        let (end, breaks) = self.check_loop_body(n.get_statements());
+        // the update runs at the end of each iteration
+        self.state = end;
+        if let Some(update) = n.get_update() {
+            self.visit_statement(update);
+        }
+        self.state = if is_infinite { breaks } else { before.join(breaks) };
+        self.scopes.pop();
+    }
+
+    fn visit_variable_declaration(&mut self, n: &mut VariableDeclaration) {
+        let declaration = n.get_span();
+        match n.get_value() {
+            Some(e) => self.visit_expression(e),
+            None => {
+                self.state.unassigned.insert(declaration);
+            }
+        }
+        self.declare(n.get_name(), declaration);
+    }
+
+    fn visit_variable_affectation(&mut self, n: &mut VariableAffectation) {
+        self.visit_expression(n.get_value());
+        let receiver = n.get_receiver();
+        match receiver.get_parts().as_mut_slice() {
+            [QualifiedExpressionPart::Identifier(i)] => {
+                self.assign(i.get_name());
+            },
+            _ => self.visit_qualified_expression(receiver),
+        }
+    }
+
+    fn visit_qualified_expression(&mut self, n: &mut QualifiedExpression) {
+        for (index, part) in n.get_parts().iter_mut().enumerate() {
+            match (index, part) {
+                (0, QualifiedExpressionPart::Identifier(i)) => {
+                    self.read(i.get_name(), i.get_span());
+                },
+                // closures stored in variables are called by name
+                (0, QualifiedExpressionPart::MethodCall(call)) => {
+                    self.read(call.get_name(), call.get_span());
+                    self.visit_function_call(call);
+                },
+                (_, part) => self.visit_qualified_expression_part(part),
+            }
+        }
+    }
+}
@@ -4,31 +4,33 @@ use lang_parser::ast::*;
 use super::super::ast_visitor::AstVisitor;
 use source_file::SourceFile;
 
+use errors::diagnostic::DiagnosticSink;
+use errors::codes::{NATIVE_FUNCTION_BODY, BODYLESS_FUNCTION};
 use errors::functions::function_err::{function_error};
 
 /**
  * Checks that freestanding functions have a body or are .native
  */
-pub fn check_functions_body(s: &mut SourceFile) {
-    let mut checker = FunctionsAttributesChecker::new(String::from(s.get_path()));
+pub fn check_functions_body(s: &mut SourceFile, sink: &mut DiagnosticSink) {
+    let mut checker = FunctionsAttributesChecker::new(sink);
     checker.visit_file(s.get_ast());
 }
 
-struct FunctionsAttributesChecker {
-    file: String,
+struct FunctionsAttributesChecker<'a> {
+    sink: &'a mut DiagnosticSink,
     namespace: String,
 }
 
-impl FunctionsAttributesChecker {
-    pub fn new(file: String) -> Self {
+impl<'a> FunctionsAttributesChecker<'a> {
+    pub fn new(sink: &'a mut DiagnosticSink) -> Self {
         FunctionsAttributesChecker {
-            file,
+            sink,
             namespace: String::from(""),
         }
     }
 }
 
-impl AstVisitor for FunctionsAttributesChecker {
+impl<'a> AstVisitor for FunctionsAttributesChecker<'a> {
     fn visit_file(&mut self, n: &mut File) {
         self.namespace = String::from(n.get_namespace());
         for entity in n.get_entities() {
@@ -44,20 +46,20 @@ impl AstVisitor for FunctionsAttributesChecker {
     fn visit_function(&mut self, n: &mut Function) {
         let mut is_native = false;
         let name = String::from(n.get_name());
-        let pos = n.get_pos();
+        let span = n.get_span();
         
         for attribute in n.get_attributes() {
             if attribute.get_name() == "native" {
                 is_native = true;
-            } else if attribute.get_name() == "abstract" {
-                function_error("Freestanding functions cannot be abstract", &self.namespace, &name, &self.file, pos);
             }
         }
 
         if n.has_body() && is_native {
-            function_error(".native freestanding functions cannot have a body", &self.namespace, &name, &self.file, pos);
+            self.sink.push(function_error(NATIVE_FUNCTION_BODY, ".native freestanding functions cannot have a body",
+                    &self.namespace, &name, span));
         } else if !n.has_body() && !is_native {
-            function_error("Non .natives freestanding functions must have a body", &self.namespace, &name, &self.file, pos);
+            self.sink.push(function_error(BODYLESS_FUNCTION, "Non .natives freestanding functions must have a body",
+                    &self.namespace, &name, span));
         }
     }
 }
\ No newline at end of file
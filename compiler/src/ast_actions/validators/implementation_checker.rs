@@ -0,0 +1,218 @@
+extern crate lang_parser;
+
+use lang_parser::ast::*;
+use source_file::SourceFile;
+use ast_actions::ast_visitor::AstVisitor;
+use symbol_table::{GlobalSymbolTable, NSTEntry, CSTEntry, ClassSymbolTable, mangle, member_name, get_overloads, substitute};
+use class_hierarchy::ClassHierarchy;
+use name_resolution::OBJECT_METHODS;
+use errors::diagnostic::DiagnosticSink;
+use errors::codes::{MISSING_IMPLEMENTATION, INCOMPATIBLE_OVERRIDE};
+use errors::classes::class_err::{class_error, class_member_error};
+use std::collections::{HashMap, HashSet};
+
+/**
+ * Checks that the concrete classes implement the abstract methods of their
+ * superclasses and the methods of their interfaces, and that the methods
+ * overriding an inherited one keep its param and return types
+ */
+pub fn check_implementations(s: &mut SourceFile, gst: &GlobalSymbolTable, hierarchy: &ClassHierarchy,
+        sink: &mut DiagnosticSink) {
+    let mut checker = ImplementationChecker::new(gst, hierarchy, sink);
+    checker.visit_file(s.get_ast());
+}
+
+struct ImplementationChecker<'a> {
+    gst: &'a GlobalSymbolTable,
+    hierarchy: &'a ClassHierarchy,
+    sink: &'a mut DiagnosticSink,
+    namespace: String,
+}
+
+impl<'a> ImplementationChecker<'a> {
+    pub fn new(gst: &'a GlobalSymbolTable, hierarchy: &'a ClassHierarchy, sink: &'a mut DiagnosticSink) -> Self {
+        ImplementationChecker {
+            gst,
+            hierarchy,
+            sink,
+            namespace: String::from(""),
+        }
+    }
+
+    /**
+     * Type params and symbol table of a compiled class
+     */
+    fn class_declaration(&self, class: &str) -> Option<(&'a Vec<TypeParam>, &'a ClassSymbolTable)> {
+        let (namespace, name) = class.rsplit_once('.')?;
+        match self.gst.get(namespace)?.get(name) {
+            Some(NSTEntry::Class(type_params, _, _, _, cst)) => Some((type_params, cst)),
+            _ => None
+        }
+    }
+
+    /**
+     * The class and its compiled ancestors in their linearised order, with
+     * the types the type params of each ancestor stand for in the class
+     */
+    fn ancestors(&self, class: &str) -> Vec<(String, HashMap<String, TypeRef>)> {
+        let linearised = match self.hierarchy.get_ancestors(class) {
+            Some(linearised) => linearised,
+            None => return vec![]
+        };
+        let mut ancestors = vec![];
+        // ancestor -> type args given by the nearest class extending it
+        let mut all_type_args: HashMap<String, HashMap<String, TypeRef>> = HashMap::new();
+        for current in linearised {
+            let type_args = all_type_args.remove(current).unwrap_or_default();
+            for (parent, parent_type) in self.hierarchy.get_parents(current) {
+                let mut parent_args: HashMap<String, TypeRef> = HashMap::new();
+                if let (Some((type_params, _)), TypeRef::Named(t)) = (self.class_declaration(parent), parent_type) {
+                    for (param, arg) in type_params.iter().zip(t.clone().get_type_args().iter()) {
+                        parent_args.insert(String::from(param.get_name()), substitute(arg, &type_args));
+                    }
+                }
+                all_type_args.entry(parent.clone()).or_insert(parent_args);
+            }
+            if self.class_declaration(current).is_some() {
+                ancestors.push((current.clone(), type_args));
+            }
+        }
+        ancestors
+    }
+
+    /**
+     * Compares each method of the class with the nearest inherited method it
+     * overrides, the one with the same name and param types, `init` methods
+     * create objects of their own class and are not compared
+     */
+    fn check_overrides(&mut self, class: &str, ancestors: &[(String, HashMap<String, TypeRef>)]) {
+        let (_, cst) = match self.class_declaration(&format!("{}.{}", self.namespace, class)) {
+            Some(declaration) => declaration,
+            None => return
+        };
+        let mut methods: Vec<(&String, &CSTEntry)> = cst.iter().collect();
+        methods.sort_by_key(|(_, entry)| entry.get_span().get_start_byte());
+        for (key, entry) in methods {
+            let name = member_name(key);
+            let (type_params, param_types, return_type, declaration) = match entry {
+                CSTEntry::Method(type_params, param_types, return_type, declaration, _) if name != "init" => {
+                    (type_params, param_types, return_type, *declaration)
+                },
+                _ => continue
+            };
+            let overridden = ancestors.iter().skip(1).find_map(|(ancestor, type_args)| {
+                get_overloads(self.class_declaration(ancestor)?.1, name).into_iter().find_map(|(_, entry)| match entry {
+                    CSTEntry::Method(a_type_params, a_param_types, a_return_type, a_declaration, _) => {
+                        // the type params of the methods are matched by position
+                        let mut type_args = type_args.clone();
+                        for (a_param, param) in a_type_params.iter().zip(type_params.iter()) {
+                            let param_type = TypeRef::Named(NamedType::new(param.get_span(), String::from(param.get_name()), vec![]));
+                            type_args.insert(String::from(a_param.get_name()), param_type);
+                        }
+                        let expected_params: Vec<TypeRef> = a_param_types.iter().map(|t| substitute(t, &type_args)).collect();
+                        let is_same = a_type_params.len() == type_params.len()
+                                && expected_params.len() == param_types.len()
+                                && expected_params.iter().zip(param_types.iter()).all(|(a, b)| a.is_same_type(b));
+                        if is_same {
+                            Some((ancestor, substitute(a_return_type, &type_args), *a_declaration))
+                        } else {
+                            None
+                        }
+                    },
+                    CSTEntry::Field(..) => None
+                })
+            });
+            let (ancestor, expected_return, a_declaration) = match overridden {
+                Some(overridden) => overridden,
+                None => continue
+            };
+            if !expected_return.is_same_type(return_type) {
+                let msg = format!("`{}` overrides {}.{} with a different return type, expected {} found {}", name,
+                        ancestor, name, expected_return, return_type);
+                let diagnostic = class_member_error(INCOMPATIBLE_OVERRIDE, &msg, &self.namespace, class, name, declaration)
+                        .with_secondary(a_declaration, "overridden method declared here");
+                self.sink.push(diagnostic);
+            }
+        }
+    }
+
+    /**
+     * Signatures of the methods of an ancestor as seen from the class, the
+     * type params of the ancestor being replaced by their value
+     */
+    fn inherited_signatures(&self, ancestor: &str, type_args: &HashMap<String, TypeRef>) -> HashMap<String, String> {
+        let cst = match self.class_declaration(ancestor) {
+            Some((_, cst)) => cst,
+            None => return HashMap::new()
+        };
+        cst.iter().filter_map(|(key, entry)| match entry {
+            CSTEntry::Method(type_params, param_types, _, _, _) => {
+                let param_types: Vec<TypeRef> = param_types.iter().map(|t| substitute(t, type_args)).collect();
+                Some((key.clone(), mangle(member_name(key), type_params, &param_types)))
+            },
+            CSTEntry::Field(..) => None
+        }).collect()
+    }
+
+    /**
+     * Reports the abstract methods of the ancestors of a concrete class that
+     * neither the class nor its superclasses implement with the same param
+     * types
+     */
+    fn check_completeness(&mut self, n: &mut Class, ancestors: &[(String, HashMap<String, TypeRef>)]) {
+        let mut implemented: HashSet<String> = HashSet::new();
+        for (ancestor, type_args) in ancestors {
+            if self.hierarchy.is_interface(ancestor) {
+                continue;
+            }
+            let abstract_methods = self.hierarchy.get_abstract_methods(ancestor);
+            implemented.extend(self.inherited_signatures(ancestor, type_args).into_iter()
+                    .filter(|(key, _)| !abstract_methods.contains(key))
+                    .map(|(_, signature)| signature));
+        }
+        let mut reported: HashSet<String> = HashSet::new();
+        for (ancestor, type_args) in ancestors.iter().skip(1) {
+            let cst = match self.class_declaration(ancestor) {
+                Some((_, cst)) => cst,
+                None => continue
+            };
+            let signatures = self.inherited_signatures(ancestor, type_args);
+            for method in self.hierarchy.get_abstract_methods(ancestor) {
+                let signature = match signatures.get(method) {
+                    Some(signature) => signature.clone(),
+                    None => continue
+                };
+                if OBJECT_METHODS.contains(&member_name(method)) || implemented.contains(&signature)
+                        || !reported.insert(signature.clone()) {
+                    continue;
+                }
+                let kind = if self.hierarchy.is_interface(ancestor) { "interface" } else { "abstract class" };
+                let msg = format!("`{}` required by {} {} is not implemented", signature, kind, ancestor);
+                let diagnostic = class_error(MISSING_IMPLEMENTATION, &msg, &self.namespace, n.get_name(), n.get_span())
+                        .with_help("implement the method or mark the class .abstract")
+                        .with_secondary(cst[method].get_span(), "required here");
+                self.sink.push(diagnostic);
+            }
+        }
+    }
+}
+
+impl<'a> AstVisitor for ImplementationChecker<'a> {
+    fn visit_file(&mut self, n: &mut File) {
+        self.namespace = String::from(n.get_namespace());
+        for entity in n.get_entities() {
+            if let FirstClassEntity::Class(c) = entity {
+                self.visit_class(c);
+            }
+        }
+    }
+
+    fn visit_class(&mut self, n: &mut Class) {
+        let qualified = format!("{}.{}", self.namespace, n.get_name());
+        let ancestors = self.ancestors(&qualified);
+        self.check_overrides(n.get_name(), &ancestors);
+        if !self.hierarchy.is_abstract(&qualified) && !self.hierarchy.is_interface(&qualified) {
+            self.check_completeness(n, &ancestors);
+        }
+    }
+}
@@ -0,0 +1,195 @@
+extern crate lang_parser;
+
+use lang_parser::ast::*;
+use lang_parser::Span;
+use super::super::ast_visitor::AstVisitor;
+use super::declared_types::DeclaredTypes;
+use source_file::SourceFile;
+use symbol_table::GlobalSymbolTable;
+use import_resolution::{FileScopes, FileScope};
+use name_resolution::{Resolution, ResolutionTable};
+use errors::diagnostic::{Diagnostic, DiagnosticSink};
+use errors::codes::{NOT_INDEXABLE, INDEX_NOT_ASSIGNABLE};
+use errors::classes::class_err::{class_member_error};
+use errors::functions::function_err::{function_error};
+
+/**
+ * Resolves index expressions to the `operator[]` method of the indexed
+ * object, or to its `operator[]=` method when they are assigned, and reports
+ * the ones whose class declares no such method. The indexed names are known
+ * from the name resolution.
+ */
+pub fn check_indexes(s: &mut SourceFile, gst: &GlobalSymbolTable, scopes: &FileScopes, resolutions: &ResolutionTable,
+        sink: &mut DiagnosticSink) {
+    let scope = scopes.get(&s.get_file_id()).expect("imports are resolved before the index checks");
+    let mut checker = IndexChecker::new(gst, scope, resolutions, sink);
+    checker.visit_file(s.get_ast());
+}
+
+struct IndexChecker<'a> {
+    resolutions: &'a ResolutionTable,
+    sink: &'a mut DiagnosticSink,
+    function: String,
+    types: DeclaredTypes<'a>,
+}
+
+impl<'a> IndexChecker<'a> {
+    pub fn new(gst: &'a GlobalSymbolTable, scope: &'a FileScope, resolutions: &'a ResolutionTable,
+            sink: &'a mut DiagnosticSink) -> Self {
+        IndexChecker {
+            resolutions,
+            sink,
+            function: String::from(""),
+            types: DeclaredTypes::new(gst, scope),
+        }
+    }
+
+    fn error(&self, code: &str, msg: &str, span: Span) -> Diagnostic {
+        let namespace = self.types.get_namespace();
+        match self.types.get_class() {
+            Some(class) => class_member_error(code, msg, namespace, class, &self.function, span),
+            None => function_error(code, msg, namespace, &self.function, span),
+        }
+    }
+
+    /**
+     * Name of the last of the indexed parts and what it was resolved to
+     */
+    fn resolution<'b>(&self, parts: &'b [QualifiedExpressionPart]) -> Option<(&'b str, &'a Resolution)> {
+        let (name, span) = match parts.last()? {
+            QualifiedExpressionPart::Identifier(i) => (i.get_name(), i.get_span()),
+            QualifiedExpressionPart::MethodCall(call) => (call.get_name(), call.get_span()),
+            _ => return None
+        };
+        Some((name, self.resolutions.get(&span)?))
+    }
+
+    /**
+     * Declared type of the indexed parts: the type of the field or the
+     * return type of the method their last part is resolved to, else the
+     * type of the param, variable or field they are made of
+     */
+    fn indexed_type(&self, parts: &[QualifiedExpressionPart]) -> Option<TypeRef> {
+        match self.resolution(parts) {
+            Some((_, Resolution::Field(class, declaration))) | Some((_, Resolution::Method(class, declaration))) => {
+                self.types.member_type(class, *declaration)
+            },
+            Some((_, Resolution::This(class))) | Some((_, Resolution::Super(class))) => {
+                Some(TypeRef::Named(NamedType::new(parts[0].get_span(), class.clone(), vec![])))
+            },
+            _ => self.types.parts_type(parts)
+        }
+    }
+
+    /**
+     * Checks the index at `index` in `parts` against the class of the
+     * indexed parts, when it is known
+     */
+    fn check_index(&mut self, parts: &[QualifiedExpressionPart], index: usize, method: &str) {
+        let span = parts[index].get_span();
+        if let Some((name, resolution)) = self.resolution(&parts[..index]) {
+            if let Resolution::Class(..) | Resolution::Function(..) | Resolution::Import(..) = resolution {
+                let msg = format!("`{}` refers to {}, it cannot be indexed", name, resolution);
+                let mut diagnostic = self.error(NOT_INDEXABLE, &msg, span);
+                for declaration in resolution.get_declarations() {
+                    diagnostic = diagnostic.with_secondary(declaration, "declared here");
+                }
+                self.sink.push(diagnostic);
+                return;
+            }
+        }
+        let class = match self.indexed_type(&parts[..index]) {
+            Some(TypeRef::Named(t)) => String::from(t.get_name()),
+            Some(TypeRef::Closure(t)) => {
+                let diagnostic = self.error(NOT_INDEXABLE,
+                        &format!("closures of type {} cannot be indexed", TypeRef::Closure(t)), span);
+                self.sink.push(diagnostic);
+                return;
+            },
+            None => return
+        };
+        if self.types.class_has_method(&class, method) == Some(false) {
+            let diagnostic = match method {
+                "operator[]=" => self.error(INDEX_NOT_ASSIGNABLE,
+                        &format!("{} does not declare {}, its indexes cannot be assigned", class, method), span),
+                _ => self.error(NOT_INDEXABLE,
+                        &format!("{} does not declare {}, it cannot be indexed", class, method), span),
+            };
+            self.sink.push(diagnostic);
+        }
+    }
+
+    fn check_indexes(&mut self, parts: &mut [QualifiedExpressionPart], is_assigned: bool) {
+        for index in 0..parts.len() {
+            if let QualifiedExpressionPart::Index(_) = parts[index] {
+                let is_last = index + 1 == parts.len();
+                let method = if is_assigned && is_last { "operator[]=" } else { "operator[]" };
+                self.check_index(parts, index, method);
+            }
+            self.visit_qualified_expression_part(&mut parts[index]);
+        }
+    }
+}
+
+impl<'a> AstVisitor for IndexChecker<'a> {
+    fn visit_file(&mut self, n: &mut File) {
+        self.types.set_namespace(n.get_namespace());
+        for entity in n.get_entities() {
+            match entity {
+                FirstClassEntity::Function(f) => {
+                    self.types.set_class(None);
+                    self.visit_function(f);
+                },
+                FirstClassEntity::Class(c) => {
+                    self.types.set_class(Some(c.get_name()));
+                    self.visit_class(c);
+                },
+            }
+        }
+    }
+
+    fn visit_function(&mut self, n: &mut Function) {
+        self.function = String::from(n.get_name());
+        self.types.push_scope();
+        for param in n.get_params() {
+            self.visit_param(param);
+        }
+        for stmt in n.get_statements() {
+            self.visit_statement(stmt);
+        }
+        self.types.pop_scope();
+    }
+
+    fn visit_closure(&mut self, n: &mut Closure) {
+        self.types.push_scope();
+        for param in n.get_params() {
+            self.visit_param(param);
+        }
+        for stmt in n.get_statements() {
+            self.visit_statement(stmt);
+        }
+        self.types.pop_scope();
+    }
+
+    fn visit_param(&mut self, n: &mut Param) {
+        let param_type = n.get_type().clone();
+        self.types.declare(n.get_name(), param_type);
+    }
+
+    fn visit_variable_declaration(&mut self, n: &mut VariableDeclaration) {
+        if let Some(e) = n.get_value() {
+            self.visit_expression(e);
+        }
+        let var_type = n.get_type().clone();
+        self.types.declare(n.get_name(), var_type);
+    }
+
+    fn visit_variable_affectation(&mut self, n: &mut VariableAffectation) {
+        self.check_indexes(n.get_receiver().get_parts(), true);
+        self.visit_expression(n.get_value());
+    }
+
+    fn visit_qualified_expression(&mut self, n: &mut QualifiedExpression) {
+        self.check_indexes(n.get_parts(), false);
+    }
+}
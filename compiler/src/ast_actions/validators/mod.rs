@@ -1,2 +1,10 @@
+pub mod attributes_checker;
 pub mod class_methods_checker1;
 pub mod functions_checker1;
+pub mod closure_types_checker;
+pub mod index_checker;
+pub mod type_checker;
+pub mod implementation_checker;
+pub mod flow_checker;
+mod declared_types;
+mod types;
@@ -0,0 +1,975 @@
+extern crate lang_parser;
+
+use lang_parser::ast::*;
+use lang_parser::Span;
+use super::super::ast_visitor::AstVisitor;
+use super::declared_types::DeclaredTypes;
+use super::types::Type;
+use source_file::SourceFile;
+use symbol_table::{GlobalSymbolTable, CSTEntry, get_overloads};
+use import_resolution::{FileScopes, FileScope};
+use class_hierarchy::ClassHierarchy;
+use errors::diagnostic::{Diagnostic, DiagnosticSink};
+use errors::codes::{MISMATCHED_TYPES, INVALID_OPERAND_TYPES, WRONG_ARGUMENTS_COUNT, AMBIGUOUS_CALL,
+        NO_MATCHING_OVERLOAD};
+use errors::classes::class_err::{class_member_error};
+use errors::functions::function_err::{function_error};
+use std::collections::HashMap;
+
+/**
+ * Computes the type of every expression and checks declarations,
+ * assignments, call arguments, returns and conditions against the declared
+ * types. Operations on objects are resolved to their `operator` methods.
+ * Whatever refers to a declaration the symbol table does not know about is
+ * of an unknown type and is accepted. Calls to overloaded methods are
+ * resolved to the overload that best matches the types of their arguments.
+ */
+pub fn check_types(s: &mut SourceFile, gst: &GlobalSymbolTable, scopes: &FileScopes, hierarchy: &ClassHierarchy,
+        sink: &mut DiagnosticSink) {
+    let scope = scopes.get(&s.get_file_id()).expect("imports are resolved before the types checks");
+    let mut checker = TypeChecker::new(gst, scope, hierarchy, sink);
+    checker.visit_file(s.get_ast());
+}
+
+struct TypeChecker<'a> {
+    sink: &'a mut DiagnosticSink,
+    function: String,
+    types: DeclaredTypes<'a>,
+    hierarchy: &'a ClassHierarchy,
+    // type params of the current class and function, their value is unknown
+    type_params: HashMap<String, Type>,
+    // return types of the function and its closures along with where they
+    // are declared, innermost closure last, None for closures without
+    // signature
+    return_types: Vec<Option<(Type, Span)>>,
+}
+
+/**
+ * Member of a class, the type params of its class replaced by their value
+ */
+enum Member {
+    Field(Type),
+    // the overloads of the method
+    Method(Vec<Signature>),
+}
+
+/**
+ * Param types and where they are declared, return type, and declaration of
+ * a method overload
+ */
+#[derive(Clone)]
+struct Signature {
+    params: Vec<(Type, Span)>,
+    return_type: Type,
+    declaration: Option<Span>,
+}
+
+impl<'a> TypeChecker<'a> {
+    pub fn new(gst: &'a GlobalSymbolTable, scope: &'a FileScope, hierarchy: &'a ClassHierarchy,
+            sink: &'a mut DiagnosticSink) -> Self {
+        TypeChecker {
+            sink,
+            function: String::from(""),
+            types: DeclaredTypes::new(gst, scope),
+            hierarchy,
+            type_params: HashMap::new(),
+            return_types: vec![],
+        }
+    }
+
+    fn error(&self, code: &str, msg: &str, span: Span) -> Diagnostic {
+        let namespace = self.types.get_namespace();
+        match self.types.get_class() {
+            Some(class) => class_member_error(code, msg, namespace, class, &self.function, span),
+            None => function_error(code, msg, namespace, &self.function, span),
+        }
+    }
+
+    fn convert(&self, t: &TypeRef) -> Type {
+        Type::from_type_ref(t, &self.type_params)
+    }
+
+    /**
+     * Reports a value of type `found` given where `expected` is expected,
+     * `declared_at` being the declaration of the expected type
+     */
+    fn check_assignable(&mut self, expected: &Type, found: &Type, span: Span, declared_at: Option<(Span, &str)>) {
+        if self.is_assignable(expected, found) {
+            return;
+        }
+        let mut diagnostic = self.error(MISMATCHED_TYPES, &format!("mismatched types, expected {}, found {}", expected, found), span);
+        if let Some((declared_span, label)) = declared_at {
+            diagnostic = diagnostic.with_secondary(declared_span, label);
+        }
+        self.sink.push(diagnostic);
+    }
+
+    /**
+     * Checks the value given to something of the declared type `expected`,
+     * closure litterals are left to the closure types checker
+     */
+    fn check_value(&mut self, expected: &Type, value: &mut Expression, declared_at: Option<(Span, &str)>) {
+        let span = value.get_span();
+        let found = self.expression_type(value);
+        if let (Type::Closure(..), true) = (expected, is_closure_litteral(value)) {
+            return;
+        }
+        self.check_assignable(expected, &found, span, declared_at);
+    }
+
+    fn is_assignable(&self, expected: &Type, found: &Type) -> bool {
+        match (expected, found) {
+            (Type::Unknown, _) | (_, Type::Unknown) => true,
+            (Type::ClassRef(_), _) | (_, Type::ClassRef(_)) => false,
+            (_, Type::Named(n, _)) if n == "Void" => expected.is_named("Void"),
+            (Type::Named(n, _), _) if n == "Void" => false,
+            (_, Type::Null) => !expected.is_primitive(),
+            (Type::Named(n, _), Type::IntegerLitteral(_)) | (Type::Named(n, _), Type::FloatLitteral) => {
+                found.litteral_fits(n) || n == "Object"
+            },
+            (Type::Named(n, _), _) if n == "Object" || n == "Interface" => true,
+            // a plain `Closure` accepts any closure
+            (Type::Named(n, args), Type::Closure(..)) => n == "Closure" && args.is_empty(),
+            (Type::Closure(params, return_type), Type::Closure(found_params, found_return_type)) => {
+                params.len() == found_params.len()
+                    && params.iter().zip(found_params.iter()).all(|(a, b)| self.is_compatible(a, b))
+                    && self.is_assignable(return_type, found_return_type)
+            },
+            (Type::Named(n, args), Type::Named(m, found_args)) if self.is_same_class(n, m) => {
+                // missing type args are not checked: `List.new()` is a `List<T>`
+                args.is_empty() || found_args.is_empty() || (args.len() == found_args.len()
+                    && args.iter().zip(found_args.iter()).all(|(a, b)| self.is_compatible(a, b)))
+            },
+            (Type::Named(n, _), Type::Named(m, _)) => {
+                let is_builtin = |t: &Type, name: &str| t.is_primitive() || name == "String";
+                !is_builtin(expected, n) && !is_builtin(found, m)
+                    && self.is_subclass(m, n) != Some(false)
+            },
+            _ => false
+        }
+    }
+
+    /**
+     * Whether two class names refer to the same class, an imported class
+     * being also known by its alias
+     */
+    fn is_same_class(&self, a: &str, b: &str) -> bool {
+        a == b || self.types.resolve(a).is_some_and(|class| Some(class) == self.types.resolve(b))
+    }
+
+    /**
+     * Whether `ancestor` is among the linearised ancestors of the class,
+     * None when the answer cannot be known because the class or one of its
+     * ancestors is not compiled
+     */
+    fn is_subclass(&self, class: &str, ancestor: &str) -> Option<bool> {
+        let qualified = |name: &str| match self.types.resolve(name) {
+            Some((namespace, name)) => format!("{}.{}", namespace, name),
+            None => String::from(name)
+        };
+        let ancestors = self.hierarchy.get_ancestors(&qualified(class))?;
+        let ancestor = qualified(ancestor);
+        if ancestors.contains(&ancestor) {
+            Some(true)
+        } else if ancestors.iter()
+                .all(|a| a == "Object" || a == "Interface" || self.hierarchy.get_ancestors(a).is_some()) {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    /**
+     * Type args are compared both ways, a `List<Int>` can be given a list
+     * of integer litterals
+     */
+    fn is_compatible(&self, a: &Type, b: &Type) -> bool {
+        self.is_assignable(a, b) || self.is_assignable(b, a)
+    }
+
+    /**
+     * Looks up a field or method in a class and its superclasses, the type
+     * params of the classes being replaced by `type_args` and the type args
+     * given to the superclasses. The overloads of a method are gathered from
+     * the whole chain, an overriding method hiding the one it overrides.
+     */
+    fn find_member(&self, class: &str, type_args: &[Type], name: &str) -> Option<Member> {
+        // the superclasses are looked up in the namespace of the class
+        let (namespace, mut class) = self.types.resolve(class)?;
+        let mut type_args = type_args.to_vec();
+        let mut overloads: Vec<Signature> = vec![];
+        // guards against inheritance cycles
+        let mut visited: Vec<String> = vec![];
+        while let Some((type_params, super_class, _, cst)) = self.types.namespace_class_declaration(&namespace, &class) {
+            let values: HashMap<String, Type> = type_params.iter().enumerate()
+                    .map(|(i, p)| (String::from(p.get_name()), type_args.get(i).cloned().unwrap_or(Type::Unknown)))
+                    .collect();
+            if let (Some(CSTEntry::Field(field_type, _)), true) = (cst.get(name), overloads.is_empty()) {
+                return Some(Member::Field(Type::from_type_ref(field_type, &values)));
+            }
+            for (_, entry) in get_overloads(cst, name) {
+                if let CSTEntry::Method(method_type_params, param_types, return_type, declaration, _) = entry {
+                    let mut values = values.clone();
+                    for type_param in method_type_params {
+                        values.insert(String::from(type_param.get_name()), Type::Unknown);
+                    }
+                    let params: Vec<(Type, Span)> = param_types.iter()
+                            .map(|t| (Type::from_type_ref(t, &values), t.get_span()))
+                            .collect();
+                    let is_overridden = overloads.iter().any(|o| {
+                        o.params.len() == params.len() && o.params.iter().zip(params.iter()).all(|(a, b)| a.0 == b.0)
+                    });
+                    if !is_overridden {
+                        let return_type = Type::from_type_ref(return_type, &values);
+                        overloads.push(Signature { params, return_type, declaration: Some(*declaration) });
+                    }
+                }
+            }
+            visited.push(class);
+            match Type::from_type_ref(super_class, &values) {
+                Type::Named(n, args) if !visited.contains(&n) => {
+                    class = n;
+                    type_args = args;
+                },
+                _ => break
+            }
+        }
+        if overloads.is_empty() { None } else { Some(Member::Method(overloads)) }
+    }
+
+    /**
+     * Overloads of a method of the class of `receiver`, none when it is not
+     * known
+     */
+    fn find_method(&self, receiver: &Type, name: &str) -> Vec<Signature> {
+        let class = match receiver.get_class() {
+            Some(class) => class,
+            None => return vec![]
+        };
+        let type_args = match receiver {
+            Type::Named(_, args) => args.clone(),
+            _ => vec![],
+        };
+        match self.find_member(class, &type_args, name) {
+            Some(Member::Method(overloads)) => overloads,
+            _ => vec![]
+        }
+    }
+
+    /**
+     * Types the arguments of a call and checks them against the params of
+     * the called function
+     */
+    fn check_call(&mut self, call: &mut FunctionCall, params: Option<&[(Type, Span)]>) {
+        let params = match params {
+            Some(params) => params,
+            None => {
+                for arg in call.get_param_exprs() {
+                    self.expression_type(arg);
+                }
+                return;
+            }
+        };
+        let args_count = call.get_param_exprs().len();
+        if params.len() != args_count {
+            let msg = format!("`{}` expects {} arguments, found {}", call.get_name(), params.len(), args_count);
+            let diagnostic = self.error(WRONG_ARGUMENTS_COUNT, &msg, call.get_span());
+            self.sink.push(diagnostic);
+            for arg in call.get_param_exprs() {
+                self.expression_type(arg);
+            }
+            return;
+        }
+        for (arg, (param_type, param_span)) in call.get_param_exprs().iter_mut().zip(params.iter()) {
+            self.check_value(param_type, arg, Some((*param_span, "parameter declared here")));
+        }
+    }
+
+    /**
+     * Checks a call to one of `overloads` and gives the type it returns,
+     * unknown when the called overload cannot be known
+     */
+    fn call_type(&mut self, call: &mut FunctionCall, overloads: &[Signature]) -> Type {
+        match overloads {
+            [] => {
+                self.check_call(call, None);
+                Type::Unknown
+            },
+            [signature] => {
+                self.check_call(call, Some(&signature.params));
+                signature.return_type.clone()
+            },
+            _ => {
+                let args: Vec<Type> = call.get_param_exprs().iter_mut().map(|arg| self.expression_type(arg)).collect();
+                let name = String::from(call.get_name());
+                match self.select_overload(&name, overloads, &args, call.get_span()) {
+                    Some(signature) => signature.return_type.clone(),
+                    None => Type::Unknown
+                }
+            }
+        }
+    }
+
+    /**
+     * Picks the overload called with arguments of types `args`: among the
+     * ones accepting them, the one whose params are of the exact type of
+     * the most arguments. Calls that no overload or several equally good
+     * ones accept are reported, unless an argument is of an unknown type.
+     */
+    fn select_overload<'s>(&mut self, name: &str, overloads: &'s [Signature], args: &[Type], span: Span)
+            -> Option<&'s Signature> {
+        let accepted: Vec<(usize, &Signature)> = overloads.iter()
+                .filter(|o| o.params.len() == args.len()
+                        && o.params.iter().zip(args.iter()).all(|((param, _), arg)| self.is_assignable(param, arg)))
+                .map(|o| (o.params.iter().zip(args.iter()).filter(|((param, _), arg)| is_exact_match(param, arg)).count(), o))
+                .collect();
+        let best_score = accepted.iter().map(|(score, _)| *score).max();
+        let best: Vec<&Signature> = accepted.iter()
+                .filter(|(score, _)| Some(*score) == best_score)
+                .map(|(_, o)| *o)
+                .collect();
+        if best.len() == 1 {
+            return Some(best[0]);
+        }
+        if !best.is_empty() && args.iter().any(|arg| arg.is_unknown()) {
+            return None;
+        }
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        let (code, msg, candidates) = if best.is_empty() {
+            (NO_MATCHING_OVERLOAD, format!("no overload of `{}` accepts ({})", name, args.join(", ")), overloads.iter().collect())
+        } else {
+            (AMBIGUOUS_CALL, format!("call to `{}` with ({}) is ambiguous", name, args.join(", ")), best)
+        };
+        let mut diagnostic = self.error(code, &msg, span);
+        for candidate in candidates {
+            if let Some(declaration) = candidate.declaration {
+                diagnostic = diagnostic.with_secondary(declaration, "candidate declared here");
+            }
+        }
+        self.sink.push(diagnostic);
+        None
+    }
+
+    fn expression_type(&mut self, e: &mut Expression) -> Type {
+        match e {
+            Expression::Operation(o) => self.operation_type(o),
+            Expression::Unary(u) => self.unary_operation_type(u),
+            Expression::Expr(q) => self.parts_type(q.get_parts()),
+        }
+    }
+
+    /**
+     * Type of a chain of qualified expression parts, each part being looked
+     * up in the type of the previous one
+     */
+    fn parts_type(&mut self, parts: &mut [QualifiedExpressionPart]) -> Type {
+        let mut current: Option<Type> = None;
+        for part in parts.iter_mut() {
+            current = Some(match current {
+                None => self.first_part_type(part),
+                Some(receiver) => self.member_type(&receiver, part),
+            });
+        }
+        current.unwrap_or(Type::Unknown)
+    }
+
+    fn first_part_type(&mut self, part: &mut QualifiedExpressionPart) -> Type {
+        match part {
+            QualifiedExpressionPart::Identifier(i) => self.identifier_type(i.get_name()),
+            QualifiedExpressionPart::MethodCall(call) => {
+                let function = self.types.function_declaration(call.get_name()).map(|(type_params, param_types, return_type)| {
+                    let mut values = self.type_params.clone();
+                    for type_param in type_params {
+                        values.insert(String::from(type_param.get_name()), Type::Unknown);
+                    }
+                    let params: Vec<(Type, Span)> = param_types.iter()
+                            .map(|t| (Type::from_type_ref(t, &values), t.get_span()))
+                            .collect();
+                    vec![Signature { params, return_type: Type::from_type_ref(return_type, &values), declaration: None }]
+                });
+                // methods of the current class can be called without `this`
+                let overloads = function.unwrap_or_else(|| match self.types.get_class() {
+                    Some(class) => self.find_method(&Type::named(class), call.get_name()),
+                    None => vec![]
+                });
+                self.call_type(call, &overloads)
+            },
+            QualifiedExpressionPart::Integer(i) if i.has_suffix() => Type::named(i.get_type().get_name()),
+            QualifiedExpressionPart::Integer(i) => Type::IntegerLitteral(Some(*i.get_value() as i128)),
+            QualifiedExpressionPart::Float(f) if f.has_suffix() => Type::named(f.get_type().get_name()),
+            QualifiedExpressionPart::Float(_) => Type::FloatLitteral,
+            QualifiedExpressionPart::StringLitteral(_) => Type::named("String"),
+            QualifiedExpressionPart::Char(_) => Type::named("Char"),
+            QualifiedExpressionPart::Boolean(_) => Type::named("Bool"),
+            QualifiedExpressionPart::Null { span: _ } => Type::Null,
+            QualifiedExpressionPart::Closure(c) => self.closure_type(c),
+            QualifiedExpressionPart::ParenExpr(e) => self.expression_type(e),
+            QualifiedExpressionPart::Index(i) => {
+                self.expression_type(i.get_expr());
+                Type::Unknown
+            },
+            QualifiedExpressionPart::Array(a) => {
+                let items: Vec<Type> = a.get_items().iter_mut().map(|item| self.expression_type(item)).collect();
+                Type::Named(String::from("List"), vec![common_type(&items)])
+            },
+            QualifiedExpressionPart::Map(m) => {
+                let mut keys: Vec<Type> = vec![];
+                let mut values: Vec<Type> = vec![];
+                for (key, value) in m.get_entries() {
+                    keys.push(self.expression_type(key));
+                    values.push(self.expression_type(value));
+                }
+                Type::Named(String::from("Map"), vec![common_type(&keys), common_type(&values)])
+            },
+        }
+    }
+
+    /**
+     * Variable, `this`, `self`, `super` or class name
+     */
+    fn identifier_type(&self, name: &str) -> Type {
+        if let Some(var_type) = self.types.variable_type(name) {
+            return self.convert(&var_type);
+        }
+        match (name, self.types.get_class()) {
+            ("this", Some(class)) | ("self", Some(class)) => Type::named(class),
+            ("super", Some(class)) => match self.types.class_declaration(class) {
+                Some((_, super_class, _, _)) => self.convert(super_class),
+                None => Type::Unknown
+            },
+            _ if self.types.class_declaration(name).is_some() => Type::ClassRef(String::from(name)),
+            _ => Type::Unknown
+        }
+    }
+
+    /**
+     * Type of a part following an expression of type `receiver`
+     */
+    fn member_type(&mut self, receiver: &Type, part: &mut QualifiedExpressionPart) -> Type {
+        match part {
+            QualifiedExpressionPart::Identifier(i) => {
+                let class = match receiver.get_class() {
+                    Some(class) => class,
+                    None => return Type::Unknown
+                };
+                let type_args = match receiver {
+                    Type::Named(_, args) => args.clone(),
+                    _ => vec![],
+                };
+                match self.find_member(class, &type_args, i.get_name()) {
+                    Some(Member::Field(field_type)) => field_type,
+                    _ => Type::Unknown
+                }
+            },
+            QualifiedExpressionPart::MethodCall(call) => {
+                let overloads = match receiver {
+                    // objects are created by the `.init` methods, which one
+                    // is called is not known here
+                    Type::ClassRef(class) if call.get_name() == "new" => {
+                        self.check_call(call, None);
+                        return Type::named(class);
+                    },
+                    Type::ClassRef(class) => self.find_method(&Type::named(class), call.get_name()),
+                    Type::Closure(params, return_type) if call.get_name() == "call" => {
+                        let params: Vec<(Type, Span)> = params.iter().map(|p| (p.clone(), call.get_span())).collect();
+                        vec![Signature { params, return_type: (**return_type).clone(), declaration: None }]
+                    },
+                    _ => self.find_method(receiver, call.get_name()),
+                };
+                self.call_type(call, &overloads)
+            },
+            QualifiedExpressionPart::Index(i) => {
+                // indexes of classes without `operator[]` are reported by
+                // the index checker
+                let span = i.get_span();
+                match self.find_method(receiver, "operator[]").as_slice() {
+                    [] => {
+                        self.expression_type(i.get_expr());
+                        Type::Unknown
+                    },
+                    [signature] => {
+                        if let Some((param_type, param_span)) = signature.params.first() {
+                            self.check_value(param_type, i.get_expr(), Some((*param_span, "index declared here")));
+                        }
+                        signature.return_type.clone()
+                    },
+                    overloads => {
+                        let index_type = self.expression_type(i.get_expr());
+                        match self.select_overload("operator[]", overloads, &[index_type], span) {
+                            Some(signature) => signature.return_type.clone(),
+                            None => Type::Unknown
+                        }
+                    }
+                }
+            },
+            _ => {
+                self.first_part_type(part);
+                Type::Unknown
+            }
+        }
+    }
+
+    /**
+     * Checks the body of a closure litteral and gives its type, a closure
+     * without signature takes no parameter
+     */
+    fn closure_type(&mut self, c: &mut Closure) -> Type {
+        self.visit_closure(c);
+        let params = c.get_params().iter_mut().map(|p| self.convert(p.get_type())).collect();
+        let return_type = match c.get_return_type() {
+            Some(t) => self.convert(t),
+            None => Type::Unknown
+        };
+        Type::Closure(params, Box::new(return_type))
+    }
+
+    /**
+     * Calls the `operator` method of the class of `operand` when it declares
+     * one, None when `operand` is not an object of a known class
+     */
+    fn operator_overload(&mut self, operand: &Type, symbol: &str, other: Option<(&Type, Span)>,
+            span: Span, is_required: bool) -> Option<Type> {
+        let class = String::from(operand.get_class()?);
+        let method = format!("operator{}", symbol);
+        let arity = if other.is_some() { 1 } else { 0 };
+        let found = self.find_method(operand, &method);
+        let overloads: Vec<Signature> = found.iter()
+                .filter(|o| o.params.len() == arity)
+                .cloned()
+                .collect();
+        match overloads.as_slice() {
+            [signature] => {
+                if let (Some((other_type, other_span)), Some((param_type, param_span))) = (other, signature.params.first()) {
+                    self.check_assignable(param_type, other_type, other_span, Some((*param_span, "operand declared here")));
+                }
+                Some(signature.return_type.clone())
+            },
+            [] => {
+                let has_method = self.types.class_has_method(&class, &method);
+                if is_required && (!found.is_empty() || has_method == Some(false)) {
+                    let kind = if arity == 1 { "binary" } else { "unary" };
+                    let msg = format!("{} does not declare a {} {}", class, kind, method);
+                    let diagnostic = self.error(INVALID_OPERAND_TYPES, &msg, span);
+                    self.sink.push(diagnostic);
+                    return Some(Type::Unknown);
+                }
+                None
+            },
+            overloads => {
+                let args: Vec<Type> = other.iter().map(|(other_type, _)| (*other_type).clone()).collect();
+                let selected = self.select_overload(&method, overloads, &args, span);
+                Some(selected.map_or(Type::Unknown, |signature| signature.return_type.clone()))
+            }
+        }
+    }
+
+    fn invalid_operands(&mut self, symbol: &str, lval: &Type, rval: Option<&Type>, span: Span) {
+        let msg = match rval {
+            Some(rval) => format!("cannot apply `{}` to {} and {}", symbol, lval, rval),
+            None => format!("cannot apply `{}` to {}", symbol, lval),
+        };
+        let diagnostic = self.error(INVALID_OPERAND_TYPES, &msg, span);
+        self.sink.push(diagnostic);
+    }
+
+    fn operation_type(&mut self, o: &mut Operation) -> Type {
+        let span = o.get_span();
+        let lval = self.expression_type(o.get_lval());
+        let rval_span = o.get_rval().get_span();
+        let rval = self.expression_type(o.get_rval());
+        let op = o.get_op().clone();
+        let symbol = operator_symbol(&op);
+        let is_comparison = matches!(op, OperationType::Eqal | OperationType::NotEqual
+                | OperationType::GreaterOrEqual | OperationType::LowerOrEqual
+                | OperationType::GreaterThan | OperationType::LowerThan
+                | OperationType::Or | OperationType::And);
+        // objects are compared by reference when they have no `operator==`
+        let is_equality = matches!(op, OperationType::Eqal | OperationType::NotEqual);
+
+        if let Some(t) = self.operator_overload(&lval, symbol, Some((&rval, rval_span)), span, !is_equality) {
+            return t;
+        }
+        let is_unknown = |t: &Type| t.is_unknown() || (t.get_class().is_some() && !t.is_named("String"));
+        if is_unknown(&lval) || (rval.is_unknown() && !is_equality) {
+            return if is_comparison { Type::named("Bool") } else { Type::Unknown };
+        }
+
+        let result = match op {
+            OperationType::Or | OperationType::And => {
+                if lval.is_named("Bool") && rval.is_named("Bool") { Some(Type::named("Bool")) } else { None }
+            },
+            OperationType::Eqal | OperationType::NotEqual => {
+                if self.is_compatible(&lval, &rval) { Some(Type::named("Bool")) } else { None }
+            },
+            OperationType::GreaterOrEqual | OperationType::LowerOrEqual
+                    | OperationType::GreaterThan | OperationType::LowerThan => {
+                let is_ordered = numeric_type(&lval, &rval).is_some()
+                    || (lval.is_named("Char") && rval.is_named("Char"))
+                    || (lval.is_named("String") && rval.is_named("String"));
+                if is_ordered { Some(Type::named("Bool")) } else { None }
+            },
+            OperationType::ShiftLeft | OperationType::ShiftRight => {
+                if lval.is_integer() && rval.is_integer() { Some(forget_value(lval.clone())) } else { None }
+            },
+            OperationType::BitwiseOr | OperationType::BitwiseXor | OperationType::BitwiseAnd => {
+                if lval.is_named("Bool") && rval.is_named("Bool") {
+                    Some(Type::named("Bool"))
+                } else {
+                    numeric_type(&lval, &rval).filter(|t| t.is_integer())
+                }
+            },
+            OperationType::Add if lval.is_named("String") && rval.is_named("String") => Some(Type::named("String")),
+            OperationType::Add | OperationType::Minus | OperationType::Times
+                    | OperationType::Div | OperationType::Mod => numeric_type(&lval, &rval),
+        };
+        match result {
+            Some(t) => t,
+            None => {
+                self.invalid_operands(symbol, &lval, Some(&rval), span);
+                if is_comparison { Type::named("Bool") } else { Type::Unknown }
+            }
+        }
+    }
+
+    fn unary_operation_type(&mut self, u: &mut UnaryOperation) -> Type {
+        let span = u.get_span();
+        let operand = self.expression_type(u.get_expr());
+        let op = u.get_op().clone();
+        let symbol = match op {
+            UnaryOperationType::Not => "!",
+            UnaryOperationType::Negate => "-",
+            UnaryOperationType::BitwiseNot => "~",
+        };
+
+        if let Some(t) = self.operator_overload(&operand, symbol, None, span, true) {
+            return t;
+        }
+        if operand.is_unknown() || operand.get_class().is_some() {
+            return match op {
+                UnaryOperationType::Not => Type::named("Bool"),
+                _ => Type::Unknown
+            };
+        }
+
+        let result = match op {
+            UnaryOperationType::Not if operand.is_named("Bool") => Some(operand.clone()),
+            UnaryOperationType::Negate => match operand {
+                Type::IntegerLitteral(value) => Some(Type::IntegerLitteral(value.map(|v| -v))),
+                _ if operand.is_numeric() => Some(operand.clone()),
+                _ => None
+            },
+            UnaryOperationType::BitwiseNot if operand.is_integer() => Some(forget_value(operand.clone())),
+            _ => None
+        };
+        match result {
+            Some(t) => t,
+            None => {
+                self.invalid_operands(symbol, &operand, None, span);
+                match op {
+                    UnaryOperationType::Not => Type::named("Bool"),
+                    _ => Type::Unknown
+                }
+            }
+        }
+    }
+
+    fn check_condition(&mut self, condition: &mut Expression) {
+        let span = condition.get_span();
+        let condition_type = self.expression_type(condition);
+        if !self.is_assignable(&Type::named("Bool"), &condition_type) {
+            let msg = format!("conditions must be of type Bool, found {}", condition_type);
+            let diagnostic = self.error(MISMATCHED_TYPES, &msg, span);
+            self.sink.push(diagnostic);
+        }
+    }
+
+    fn visit_scoped_statements(&mut self, statements: &mut Vec<Statement>) {
+        self.types.push_scope();
+        for stmt in statements {
+            self.visit_statement(stmt);
+        }
+        self.types.pop_scope();
+    }
+}
+
+/**
+ * Type of the result of an arithmetic operation on numbers, a litteral
+ * taking the type of the other operand
+ */
+fn numeric_type(lval: &Type, rval: &Type) -> Option<Type> {
+    if !lval.is_numeric() || !rval.is_numeric() {
+        return None;
+    }
+    match (lval, rval) {
+        (Type::IntegerLitteral(_), Type::IntegerLitteral(_)) => Some(Type::IntegerLitteral(None)),
+        (Type::IntegerLitteral(_), Type::FloatLitteral)
+                | (Type::FloatLitteral, Type::IntegerLitteral(_))
+                | (Type::FloatLitteral, Type::FloatLitteral) => Some(Type::FloatLitteral),
+        (Type::Named(n, _), litteral) | (litteral, Type::Named(n, _)) if !litteral.is_named(n) => {
+            let is_litteral = matches!(litteral, Type::IntegerLitteral(_) | Type::FloatLitteral);
+            if is_litteral && litteral.litteral_fits(n) { Some(Type::named(n)) } else { None }
+        },
+        _ => Some(lval.clone())
+    }
+}
+
+/**
+ * The value of a litteral is not known once it goes through an operation
+ */
+fn forget_value(t: Type) -> Type {
+    match t {
+        Type::IntegerLitteral(_) => Type::IntegerLitteral(None),
+        t => t
+    }
+}
+
+/**
+ * Type of the items of a collection litteral, unknown when there is none
+ */
+fn common_type(items: &[Type]) -> Type {
+    let mut common = Type::Unknown;
+    for item in items {
+        common = match (&common, item) {
+            (Type::Unknown, _) | (Type::Null, _) => item.clone(),
+            (Type::IntegerLitteral(_), Type::Named(n, _)) | (Type::FloatLitteral, Type::Named(n, _))
+                    if common.litteral_fits(n) => item.clone(),
+            (Type::IntegerLitteral(_), Type::FloatLitteral) => item.clone(),
+            _ => common
+        };
+    }
+    common
+}
+
+fn operator_symbol(op: &OperationType) -> &'static str {
+    match op {
+        OperationType::Or             => "||",
+        OperationType::And            => "&&",
+        OperationType::BitwiseOr      => "|",
+        OperationType::BitwiseXor     => "^",
+        OperationType::BitwiseAnd     => "&",
+        OperationType::Eqal           => "==",
+        OperationType::NotEqual       => "!=",
+        OperationType::GreaterOrEqual => ">=",
+        OperationType::LowerOrEqual   => "<=",
+        OperationType::GreaterThan    => ">",
+        OperationType::LowerThan      => "<",
+        OperationType::ShiftLeft      => "<<",
+        OperationType::ShiftRight     => ">>",
+        OperationType::Add            => "+",
+        OperationType::Minus          => "-",
+        OperationType::Times          => "*",
+        OperationType::Div            => "/",
+        OperationType::Mod            => "%",
+    }
+}
+
+/**
+ * Whether an argument is of the exact type of a param, litterals without
+ * suffix being Int and Double
+ */
+fn is_exact_match(param: &Type, arg: &Type) -> bool {
+    match arg {
+        Type::IntegerLitteral(_) => param.is_named("Int"),
+        Type::FloatLitteral => param.is_named("Double"),
+        Type::Unknown => false,
+        _ => param == arg
+    }
+}
+
+fn is_closure_litteral(e: &mut Expression) -> bool {
+    match e {
+        Expression::Expr(q) => matches!(q.get_parts().as_slice(), [QualifiedExpressionPart::Closure(_)]),
+        _ => false
+    }
+}
+
+impl<'a> AstVisitor for TypeChecker<'a> {
+    fn visit_file(&mut self, n: &mut File) {
+        self.types.set_namespace(n.get_namespace());
+        for entity in n.get_entities() {
+            self.type_params.clear();
+            match entity {
+                FirstClassEntity::Function(f) => {
+                    self.types.set_class(None);
+                    self.visit_function(f);
+                },
+                FirstClassEntity::Class(c) => {
+                    self.types.set_class(Some(c.get_name()));
+                    for type_param in c.get_type_params() {
+                        self.type_params.insert(String::from(type_param.get_name()), Type::Unknown);
+                    }
+                    self.visit_class(c);
+                },
+            }
+        }
+    }
+
+    fn visit_function(&mut self, n: &mut Function) {
+        self.function = String::from(n.get_name());
+        let class_type_params = self.type_params.clone();
+        for type_param in n.get_type_params() {
+            self.type_params.insert(String::from(type_param.get_name()), Type::Unknown);
+        }
+        self.types.push_scope();
+        let return_type = self.convert(n.get_return_type());
+        self.return_types.push(Some((return_type, n.get_return_type().get_span())));
+
+        for param in n.get_params() {
+            self.visit_param(param);
+        }
+        for stmt in n.get_statements() {
+            self.visit_statement(stmt);
+        }
+
+        self.return_types.pop();
+        self.types.pop_scope();
+        self.type_params = class_type_params;
+    }
+
+    fn visit_closure(&mut self, n: &mut Closure) {
+        self.types.push_scope();
+        let return_type = n.get_return_type().clone().map(|t| (self.convert(&t), t.get_span()));
+        self.return_types.push(return_type);
+
+        for param in n.get_params() {
+            self.visit_param(param);
+        }
+        for stmt in n.get_statements() {
+            self.visit_statement(stmt);
+        }
+
+        self.return_types.pop();
+        self.types.pop_scope();
+    }
+
+    fn visit_param(&mut self, n: &mut Param) {
+        let param_type = n.get_type().clone();
+        self.types.declare(n.get_name(), param_type);
+    }
+
+    fn visit_statement(&mut self, n: &mut Statement) {
+        match n {
+            Statement::ReturnStatement { expr, span: _ } => {
+                let return_type = match self.return_types.last().cloned() {
+                    Some(Some(return_type)) => return_type,
+                    // closure without signature
+                    _ => {
+                        if let Some(e) = expr {
+                            self.expression_type(e);
+                        }
+                        return;
+                    }
+                };
+                let (return_type, return_span) = return_type;
+                // missing values and values returned from Void functions
+                // are left to the flow checker
+                match expr {
+                    Some(e) if !return_type.is_named("Void") => {
+                        self.check_value(&return_type, e, Some((return_span, "return type declared here")));
+                    },
+                    Some(e) => {
+                        self.expression_type(e);
+                    },
+                    None => {}
+                }
+            },
+            Statement::Declaration(d) => {
+                self.visit_variable_declaration(d);
+            },
+            Statement::Affectation(a) => {
+                self.visit_variable_affectation(a);
+            },
+            Statement::QualifiedExpression(q) => {
+                self.parts_type(q.get_parts());
+            },
+            Statement::If(i) => {
+                self.visit_if_statement(i);
+            },
+            Statement::While(w) => {
+                self.visit_while_statement(w);
+            },
+            Statement::For(f) => {
+                self.visit_for_statement(f);
+            },
+            Statement::Break { span: _ } | Statement::Continue { span: _ } => {}
+        }
+    }
+
+    fn visit_if_statement(&mut self, n: &mut IfStatement) {
+        self.check_condition(n.get_condition());
+        self.visit_scoped_statements(n.get_statements());
+        if let Some(else_statements) = n.get_else_statements() {
+            self.visit_scoped_statements(else_statements);
+        }
+    }
+
+    fn visit_while_statement(&mut self, n: &mut WhileStatement) {
+        self.check_condition(n.get_condition());
+        self.visit_scoped_statements(n.get_statements());
+    }
+
+    fn visit_for_statement(&mut self, n: &mut ForStatement) {
+        self.types.push_scope();
+        if let Some(init) = n.get_init() {
+            self.visit_statement(init);
+        }
+        if let Some(condition) = n.get_condition() {
+            self.check_condition(condition);
+        }
+        if let Some(update) = n.get_update() {
+            self.visit_statement(update);
+        }
+        self.visit_scoped_statements(n.get_statements());
+        self.types.pop_scope();
+    }
+
+    fn visit_variable_declaration(&mut self, n: &mut VariableDeclaration) {
+        let var_type_ref = n.get_type().clone();
+        let var_type = self.convert(&var_type_ref);
+        let label = format!("`{}` declared as {} here", n.get_name(), var_type);
+        if let Some(e) = n.get_value() {
+            self.check_value(&var_type, e, Some((var_type_ref.get_span(), &label)));
+        }
+        self.types.declare(n.get_name(), var_type_ref);
+    }
+
+    fn visit_variable_affectation(&mut self, n: &mut VariableAffectation) {
+        let span = n.get_span();
+        let parts = n.get_receiver().get_parts();
+        let is_index = matches!(parts.last(), Some(QualifiedExpressionPart::Index(_)));
+        // overloads of `operator[]=` left to pick once the value is typed,
+        // with the type of the index
+        let mut overloaded: Option<(Vec<Signature>, Type)> = None;
+        let (value_type, declared_at) = match parts.split_last_mut() {
+            // `a[i] = v` calls `operator[]=(i, v)` on `a`
+            Some((QualifiedExpressionPart::Index(index), indexed)) if is_index => {
+                let indexed_type = self.parts_type(indexed);
+                let mut overloads = self.find_method(&indexed_type, "operator[]=");
+                overloads.retain(|o| o.params.len() == 2);
+                match overloads.as_slice() {
+                    [signature] => {
+                        let params = &signature.params;
+                        self.check_value(&params[0].0, index.get_expr(), Some((params[0].1, "index declared here")));
+                        (params[1].0.clone(), Some((params[1].1, "value declared here")))
+                    },
+                    [] => {
+                        self.expression_type(index.get_expr());
+                        (Type::Unknown, None)
+                    },
+                    _ => {
+                        let index_type = self.expression_type(index.get_expr());
+                        overloaded = Some((overloads, index_type));
+                        (Type::Unknown, None)
+                    }
+                }
+            },
+            _ => (self.parts_type(parts), None),
+        };
+        if let Some((overloads, index_type)) = overloaded {
+            let value_type = self.expression_type(n.get_value());
+            self.select_overload("operator[]=", &overloads, &[index_type, value_type], span);
+            return;
+        }
+        self.check_value(&value_type, n.get_value(), declared_at);
+    }
+}
@@ -0,0 +1,156 @@
+extern crate lang_parser;
+
+use lang_parser::ast::*;
+use std::collections::HashMap;
+use std::fmt;
+
+/**
+ * Type of an expression as computed by the type checker
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub enum Type {
+    // the type cannot be known, it is compatible with every type
+    Unknown,
+    Null,
+    // integer litteral without suffix and its value when it is known, it
+    // takes the integer type it is given to
+    IntegerLitteral(Option<i128>),
+    // float litteral without suffix, it can be a Float or a Double
+    FloatLitteral,
+    Named(String, Vec<Type>),
+    Closure(Vec<Type>, Box<Type>),
+    // a class used as a value, receiver of `new` and of static methods
+    ClassRef(String),
+}
+
+const INTEGER_TYPES: &[&str] = &["Int8", "Int16", "Int", "Int64", "UInt8", "UInt16", "UInt", "UInt64"];
+const FLOAT_TYPES: &[&str] = &["Float", "Double"];
+const OTHER_PRIMITIVE_TYPES: &[&str] = &["Bool", "Char"];
+
+impl Type {
+    /**
+     * Converts a type as written in the source, the names found in
+     * `type_args` being replaced by their value
+     */
+    pub fn from_type_ref(t: &TypeRef, type_args: &HashMap<String, Type>) -> Self {
+        match t {
+            TypeRef::Named(n) => {
+                if let Some(arg) = type_args.get(n.get_name()) {
+                    return arg.clone();
+                }
+                let mut n = n.clone();
+                let args = n.get_type_args().iter().map(|arg| Type::from_type_ref(arg, type_args)).collect();
+                Type::Named(String::from(n.get_name()), args)
+            },
+            TypeRef::Closure(c) => {
+                let mut c = c.clone();
+                let params = c.get_params().iter().map(|param| Type::from_type_ref(param, type_args)).collect();
+                let return_type = Type::from_type_ref(c.get_return_type(), type_args);
+                Type::Closure(params, Box::new(return_type))
+            },
+        }
+    }
+
+    pub fn named(name: &str) -> Self {
+        Type::Named(String::from(name), vec![])
+    }
+
+    pub fn is_unknown(&self) -> bool {
+        *self == Type::Unknown
+    }
+
+    pub fn is_named(&self, name: &str) -> bool {
+        match self {
+            Type::Named(n, _) => n == name,
+            _ => false
+        }
+    }
+
+    /**
+     * Builtin value types, they cannot be null
+     */
+    pub fn is_primitive(&self) -> bool {
+        match self {
+            Type::Named(n, _) => INTEGER_TYPES.contains(&n.as_str()) || FLOAT_TYPES.contains(&n.as_str())
+                    || OTHER_PRIMITIVE_TYPES.contains(&n.as_str()),
+            Type::IntegerLitteral(_) | Type::FloatLitteral => true,
+            _ => false
+        }
+    }
+
+    pub fn is_integer(&self) -> bool {
+        match self {
+            Type::Named(n, _) => INTEGER_TYPES.contains(&n.as_str()),
+            Type::IntegerLitteral(_) => true,
+            _ => false
+        }
+    }
+
+    pub fn is_numeric(&self) -> bool {
+        match self {
+            Type::Named(n, _) => INTEGER_TYPES.contains(&n.as_str()) || FLOAT_TYPES.contains(&n.as_str()),
+            Type::IntegerLitteral(_) | Type::FloatLitteral => true,
+            _ => false
+        }
+    }
+
+    /**
+     * Classes whose methods can be looked up: named types that are neither
+     * primitives nor closures
+     */
+    pub fn get_class(&self) -> Option<&str> {
+        match self {
+            Type::Named(n, _) if !self.is_primitive() && n != "Void" => Some(n),
+            _ => None
+        }
+    }
+
+    /**
+     * Whether a litteral of this type fits in the builtin type `name`
+     */
+    pub fn litteral_fits(&self, name: &str) -> bool {
+        match self {
+            Type::IntegerLitteral(value) => match (integer_range(name), value) {
+                (Some((min, max)), Some(value)) => min <= *value && *value <= max,
+                (Some(_), None) => true,
+                // integer litterals can be given to floats
+                (None, _) => FLOAT_TYPES.contains(&name),
+            },
+            Type::FloatLitteral => FLOAT_TYPES.contains(&name),
+            _ => false
+        }
+    }
+}
+
+/**
+ * Smallest and largest values of a builtin integer type
+ */
+fn integer_range(name: &str) -> Option<(i128, i128)> {
+    match name {
+        "Int8"   => Some((i8::MIN as i128, i8::MAX as i128)),
+        "Int16"  => Some((i16::MIN as i128, i16::MAX as i128)),
+        "Int"    => Some((i32::MIN as i128, i32::MAX as i128)),
+        "Int64"  => Some((i64::MIN as i128, i64::MAX as i128)),
+        "UInt8"  => Some((0, u8::MAX as i128)),
+        "UInt16" => Some((0, u16::MAX as i128)),
+        "UInt"   => Some((0, u32::MAX as i128)),
+        "UInt64" => Some((0, u64::MAX as i128)),
+        _        => None
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let list = |types: &[Type]| types.iter().map(|t| t.to_string()).collect::<Vec<String>>().join(", ");
+        match self {
+            Type::Unknown => write!(f, "?"),
+            Type::Null => write!(f, "null"),
+            Type::IntegerLitteral(_) => write!(f, "{{integer}}"),
+            Type::FloatLitteral => write!(f, "{{float}}"),
+            Type::Named(n, args) if args.is_empty() => write!(f, "{}", n),
+            Type::Named(n, args) => write!(f, "{}<{}>", n, list(args)),
+            Type::Closure(params, return_type) => write!(f, "Closure|{}|: {}", list(params), return_type),
+            Type::ClassRef(n) => write!(f, "class {}", n),
+        }
+    }
+}
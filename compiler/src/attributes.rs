@@ -0,0 +1,137 @@
+/**
+ * Declarations an attribute can be put on
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AttributeTarget {
+    Class,
+    Field,
+    Method,
+    // freestanding function
+    Function,
+    // block of class members, its attributes are given to the members
+    Block,
+}
+
+/**
+ * Description of a built-in attribute
+ */
+#[derive(Debug)]
+pub struct AttributeInfo {
+    name: &'static str,
+    targets: &'static [AttributeTarget],
+    // attributes that cannot be put on the same declaration
+    conflicts: &'static [&'static str],
+    repeatable: bool,
+}
+
+use self::AttributeTarget::*;
+
+/**
+ * Registry of the built-in attributes, any other attribute is rejected
+ */
+pub const ATTRIBUTES: &[AttributeInfo] = &[
+    AttributeInfo {
+        name: "public",
+        targets: &[Class, Field, Method, Function, Block],
+        conflicts: &["private", "protected"],
+        repeatable: false,
+    },
+    AttributeInfo {
+        name: "private",
+        targets: &[Class, Field, Method, Function, Block],
+        conflicts: &["public", "protected"],
+        repeatable: false,
+    },
+    AttributeInfo {
+        name: "protected",
+        targets: &[Field, Method, Block],
+        conflicts: &["public", "private"],
+        repeatable: false,
+    },
+    AttributeInfo {
+        name: "abstract",
+        targets: &[Class, Method, Block],
+        conflicts: &["interface", "native", "final", "static", "init"],
+        repeatable: false,
+    },
+    AttributeInfo {
+        name: "interface",
+        targets: &[Class],
+        conflicts: &["abstract", "final"],
+        repeatable: false,
+    },
+    AttributeInfo {
+        name: "native",
+        targets: &[Method, Function, Block],
+        conflicts: &["abstract"],
+        repeatable: false,
+    },
+    AttributeInfo {
+        name: "init",
+        targets: &[Method],
+        conflicts: &["abstract", "static"],
+        repeatable: false,
+    },
+    AttributeInfo {
+        name: "getter",
+        targets: &[Method, Block],
+        conflicts: &["setter"],
+        repeatable: false,
+    },
+    AttributeInfo {
+        name: "setter",
+        targets: &[Method, Block],
+        conflicts: &["getter"],
+        repeatable: false,
+    },
+    AttributeInfo {
+        name: "static",
+        targets: &[Field, Method, Block],
+        conflicts: &["abstract", "init"],
+        repeatable: false,
+    },
+    AttributeInfo {
+        name: "final",
+        targets: &[Class, Field, Method, Block],
+        conflicts: &["abstract", "interface"],
+        repeatable: false,
+    },
+];
+
+impl AttributeTarget {
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Class => "classes",
+            Field => "fields",
+            Method => "methods",
+            Function => "freestanding functions",
+            Block => "blocks",
+        }
+    }
+}
+
+impl AttributeInfo {
+    pub fn get_name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn get_targets(&self) -> &'static [AttributeTarget] {
+        self.targets
+    }
+
+    pub fn is_repeatable(&self) -> bool {
+        self.repeatable
+    }
+
+    pub fn applies_to(&self, target: AttributeTarget) -> bool {
+        self.targets.contains(&target)
+    }
+
+    pub fn conflicts_with(&self, name: &str) -> bool {
+        self.conflicts.contains(&name)
+    }
+}
+
+pub fn find_attribute(name: &str) -> Option<&'static AttributeInfo> {
+    ATTRIBUTES.iter().find(|attribute| attribute.name == name)
+}
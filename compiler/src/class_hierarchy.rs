@@ -0,0 +1,363 @@
+extern crate lang_parser;
+
+use lang_parser::ast::*;
+use lang_parser::{Span, FileId};
+use source_file::SourceFile;
+use symbol_table::{GlobalSymbolTable, NSTEntry, mangle};
+use import_resolution::{FileScopes, FileScope, ScopeLookup};
+use name_resolution::BUILTIN_CLASSES;
+use errors::diagnostic::DiagnosticSink;
+use errors::codes::{UNKNOWN_SUPERTYPE, INVALID_SUPERCLASS, NOT_AN_INTERFACE, INHERITANCE_CYCLE};
+use errors::classes::class_err::class_error;
+use errors::suggestions::did_you_mean;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/**
+ * Superclasses and implemented interfaces of the compiled classes, keyed
+ * by qualified name. `Object`, `Interface`, the builtin classes and the
+ * classes of the standard library are known by name only.
+ */
+#[derive(Debug, Default)]
+pub struct ClassHierarchy {
+    // qualified class -> linearised ancestors
+    ancestors: HashMap<String, Vec<String>>,
+    // qualified class -> qualified names of its superclass and interfaces
+    // with the types they are written with
+    parents: HashMap<String, Vec<(String, TypeRef)>>,
+    interfaces: HashSet<String>,
+    abstract_classes: HashSet<String>,
+    // qualified class -> mangled names of the methods it declares without
+    // implementing them
+    abstract_methods: HashMap<String, Vec<String>>,
+}
+
+impl ClassHierarchy {
+    /**
+     * The class itself, its superclasses up to `Object` or `Interface`, then
+     * the interfaces they implement nearest first, each ancestor once. The
+     * chain stops at the classes whose parents are not known.
+     */
+    pub fn get_ancestors(&self, class: &str) -> Option<&Vec<String>> {
+        self.ancestors.get(class)
+    }
+
+    /**
+     * The superclass and the interfaces of a class that could be resolved,
+     * with the type arguments they are given
+     */
+    pub fn get_parents(&self, class: &str) -> &[(String, TypeRef)] {
+        self.parents.get(class).map_or(&[], |parents| parents.as_slice())
+    }
+
+    pub fn is_interface(&self, class: &str) -> bool {
+        self.interfaces.contains(class)
+    }
+
+    pub fn is_abstract(&self, class: &str) -> bool {
+        self.abstract_classes.contains(class)
+    }
+
+    /**
+     * Methods of a compiled class that its subclasses must implement: the
+     * methods of interfaces and the bodyless methods of abstract classes
+     * that are not native
+     */
+    pub fn get_abstract_methods(&self, class: &str) -> &[String] {
+        self.abstract_methods.get(class).map_or(&[], |methods| methods.as_slice())
+    }
+}
+
+/**
+ * Builtin classes that are not interfaces
+ */
+const NON_INTERFACE_BUILTINS: &[&str] = &[
+    "Object", "Closure", "String", "Void", "Bool", "Char",
+    "Int8", "Int16", "Int", "Int64", "UInt8", "UInt16", "UInt", "UInt64", "Float", "Double",
+];
+
+/**
+ * Header of a compiled class, what its hierarchy is made of
+ */
+struct ClassHeader {
+    namespace: String,
+    name: String,
+    file_id: FileId,
+    is_interface: bool,
+    is_abstract: bool,
+    abstract_methods: Vec<String>,
+    super_class: TypeRef,
+    interfaces: Vec<TypeRef>,
+}
+
+/**
+ * Parents of a class once resolved, the ones that could not be are left out
+ */
+#[derive(Default)]
+struct Parents {
+    super_class: Option<(String, TypeRef)>,
+    interfaces: Vec<(String, TypeRef)>,
+}
+
+/**
+ * Checks that the superclasses and interfaces of the classes exist, that
+ * only interfaces extend interfaces and are implemented, and that no class
+ * inherits from itself. Gives the linearised ancestors of each class.
+ */
+pub fn check_class_hierarchy(namespaces: &mut HashMap<String, Vec<SourceFile>>, gst: &GlobalSymbolTable,
+        scopes: &mut FileScopes, sink: &mut DiagnosticSink) -> ClassHierarchy {
+    let headers = collect_headers(namespaces);
+    let interfaces: HashSet<String> = headers.iter()
+            .filter(|(_, header)| header.is_interface)
+            .map(|(class, _)| class.clone())
+            .collect();
+    let mut classes: Vec<&String> = headers.keys().collect();
+    classes.sort();
+
+    let mut parents: HashMap<String, Parents> = HashMap::new();
+    for class in &classes {
+        let header = &headers[*class];
+        let scope = scopes.get_mut(&header.file_id).expect("imports are resolved before the class hierarchy");
+        let mut resolver = ParentResolver { gst, scope, sink, header, interfaces: &interfaces };
+        parents.insert((*class).clone(), resolver.resolve_parents());
+    }
+
+    report_inheritance_cycles(&classes, &headers, &parents, sink);
+
+    let ancestors = classes.iter()
+            .map(|class| ((*class).clone(), linearise(class, &parents)))
+            .collect();
+    let abstract_classes = headers.iter()
+            .filter(|(_, header)| header.is_abstract)
+            .map(|(class, _)| class.clone())
+            .collect();
+    let abstract_methods = headers.into_iter()
+            .map(|(class, header)| (class, header.abstract_methods))
+            .collect();
+    let parents = parents.into_iter()
+            .map(|(class, p)| (class, p.super_class.into_iter().chain(p.interfaces).collect()))
+            .collect();
+    ClassHierarchy {
+        ancestors,
+        parents,
+        interfaces,
+        abstract_classes,
+        abstract_methods,
+    }
+}
+
+fn collect_headers(namespaces: &mut HashMap<String, Vec<SourceFile>>) -> HashMap<String, ClassHeader> {
+    let mut headers = HashMap::new();
+    for source_files in namespaces.values_mut() {
+        for source_file in source_files {
+            let file_id = source_file.get_file_id();
+            let file = source_file.get_ast();
+            let namespace = String::from(file.get_namespace());
+            for entity in file.get_entities() {
+                if let FirstClassEntity::Class(c) = entity {
+                    let is_interface = c.get_attributes().iter().any(|a| a.get_name() == "interface");
+                    let is_abstract = c.get_attributes().iter().any(|a| a.get_name() == "abstract");
+                    let mut abstract_methods = vec![];
+                    for member in c.get_members() {
+                        if let ClassMember::Method(m) = member {
+                            let is_native = m.get_attributes().iter().any(|a| a.get_name() == "native");
+                            if is_interface || (!m.has_body() && !is_native) {
+                                let param_types: Vec<TypeRef> = m.get_params().iter_mut().map(|p| p.get_type().clone()).collect();
+                                let name = String::from(m.get_name());
+                                abstract_methods.push(mangle(&name, m.get_type_params(), &param_types));
+                            }
+                        }
+                    }
+                    let header = ClassHeader {
+                        namespace: namespace.clone(),
+                        name: String::from(c.get_name()),
+                        file_id,
+                        is_interface,
+                        is_abstract,
+                        abstract_methods,
+                        super_class: c.get_super_class().clone(),
+                        interfaces: c.get_implemented_interfaces().clone(),
+                    };
+                    // like in the symbol table, a redefined class keeps its first definition
+                    headers.entry(format!("{}.{}", namespace, c.get_name())).or_insert(header);
+                }
+            }
+        }
+    }
+    headers
+}
+
+/**
+ * Resolves the superclass and interfaces of a class from the scope of its
+ * file
+ */
+struct ParentResolver<'a> {
+    gst: &'a GlobalSymbolTable,
+    scope: &'a mut FileScope,
+    sink: &'a mut DiagnosticSink,
+    header: &'a ClassHeader,
+    // qualified names of the compiled interfaces
+    interfaces: &'a HashSet<String>,
+}
+
+impl<'a> ParentResolver<'a> {
+    fn error(&mut self, code: &str, msg: &str, span: Span) {
+        let diagnostic = class_error(code, msg, &self.header.namespace, &self.header.name, span);
+        self.sink.push(diagnostic);
+    }
+
+    fn resolve_parents(&mut self) -> Parents {
+        let mut parents = Parents::default();
+        let super_class = self.header.super_class.clone();
+        if let Some(name) = self.resolve(&super_class, "superclass") {
+            let span = super_class.get_span();
+            let is_interface = name == "Interface" || self.interfaces.contains(&name);
+            if self.header.is_interface && !is_interface {
+                self.error(INVALID_SUPERCLASS, &format!("interfaces can only extend `Interface` or other interfaces, \
+                        not {}", name), span);
+            } else if !self.header.is_interface && is_interface {
+                let diagnostic = class_error(INVALID_SUPERCLASS, &format!("{} is an interface, it cannot be extended",
+                        name), &self.header.namespace, &self.header.name, span)
+                        .with_help(&format!("implement it instead: `{}: Object | {}`", self.header.name, super_class));
+                self.sink.push(diagnostic);
+            } else {
+                parents.super_class = Some((name, super_class));
+            }
+        }
+        for interface in self.header.interfaces.clone() {
+            if let Some(name) = self.resolve(&interface, "interface") {
+                let span = interface.get_span();
+                if self.interfaces.contains(&name) || !self.is_known_class(&name) {
+                    parents.interfaces.push((name, interface));
+                } else {
+                    self.error(NOT_AN_INTERFACE, &format!("{} is not an interface, it cannot be implemented", name),
+                            span);
+                }
+            }
+        }
+        parents
+    }
+
+    /**
+     * Qualified name of a parent class, builtin classes keep their name
+     */
+    fn resolve(&mut self, parent: &TypeRef, role: &str) -> Option<String> {
+        let t = match parent {
+            TypeRef::Named(t) => t,
+            TypeRef::Closure(c) => {
+                self.error(UNKNOWN_SUPERTYPE, &format!("a closure type cannot be used as {}", role), c.get_span());
+                return None;
+            }
+        };
+        let name = t.get_name();
+        match self.scope.lookup(name) {
+            ScopeLookup::Found(namespace, declared) => {
+                match self.gst.get(&namespace).and_then(|nst| nst.get(&declared)) {
+                    Some(NSTEntry::Fun(..)) => {
+                        self.error(UNKNOWN_SUPERTYPE, &format!("`{}` is a function, not a class", name), t.get_span());
+                        None
+                    },
+                    _ => Some(format!("{}.{}", namespace, declared))
+                }
+            },
+            // reported with the imports
+            ScopeLookup::Ambiguous(_) => None,
+            ScopeLookup::NotFound if BUILTIN_CLASSES.contains(&name) => Some(String::from(name)),
+            ScopeLookup::NotFound => {
+                let mut candidates: Vec<&str> = self.scope.imported_names();
+                if let Some(nst) = self.gst.get(&self.header.namespace) {
+                    candidates.extend(nst.iter()
+                            .filter(|(_, entry)| matches!(entry, NSTEntry::Class(..)))
+                            .map(|(name, _)| name.as_str()));
+                }
+                candidates.extend(&["Object", "Interface"]);
+                let mut diagnostic = class_error(UNKNOWN_SUPERTYPE, &format!("cannot find {} `{}`", role, name),
+                        &self.header.namespace, &self.header.name, t.get_span());
+                if let Some(suggestion) = did_you_mean(name, &candidates) {
+                    diagnostic = diagnostic.with_help(&format!("did you mean `{}`?", suggestion));
+                }
+                self.sink.push(diagnostic);
+                None
+            }
+        }
+    }
+
+    /**
+     * Whether `class` is known not to be an interface: a compiled class or a
+     * builtin one that cannot be, the classes of the standard library are
+     * trusted to be interfaces when they are implemented
+     */
+    fn is_known_class(&self, class: &str) -> bool {
+        NON_INTERFACE_BUILTINS.contains(&class) || match class.rsplit_once('.') {
+            Some((namespace, _)) => self.gst.contains_key(namespace),
+            None => false
+        }
+    }
+}
+
+/**
+ * Reports each inheritance cycle once, at the parent closing it
+ */
+fn report_inheritance_cycles(classes: &[&String], headers: &HashMap<String, ClassHeader>,
+        parents: &HashMap<String, Parents>, sink: &mut DiagnosticSink) {
+    let mut done: HashSet<String> = HashSet::new();
+    for class in classes {
+        let mut path: Vec<String> = vec![];
+        visit_parents(class, headers, parents, &mut done, &mut path, sink);
+    }
+}
+
+fn visit_parents(class: &str, headers: &HashMap<String, ClassHeader>, parents: &HashMap<String, Parents>,
+        done: &mut HashSet<String>, path: &mut Vec<String>, sink: &mut DiagnosticSink) {
+    if done.contains(class) {
+        return;
+    }
+    let class_parents = match parents.get(class) {
+        Some(class_parents) => class_parents,
+        None => return
+    };
+    path.push(String::from(class));
+    for (parent, parent_type) in class_parents.super_class.iter().chain(class_parents.interfaces.iter()) {
+        match path.iter().position(|c| c == parent) {
+            Some(start) => {
+                let mut cycle = path[start..].to_vec();
+                cycle.push(parent.clone());
+                let header = &headers[class];
+                sink.push(class_error(INHERITANCE_CYCLE, &format!("inheritance cycle: {}", cycle.join(" -> ")),
+                        &header.namespace, &header.name, parent_type.get_span()));
+            },
+            None => visit_parents(parent, headers, parents, done, path, sink),
+        }
+    }
+    path.pop();
+    done.insert(String::from(class));
+}
+
+/**
+ * The class, its superclasses, then the interfaces they implement breadth
+ * first, guarding against cycles
+ */
+fn linearise(class: &str, parents: &HashMap<String, Parents>) -> Vec<String> {
+    let mut ancestors: Vec<String> = vec![];
+    let mut current = Some(String::from(class));
+    while let Some(class) = current {
+        if ancestors.contains(&class) {
+            break;
+        }
+        current = parents.get(&class).and_then(|p| p.super_class.as_ref()).map(|(name, _)| name.clone());
+        ancestors.push(class);
+    }
+    let mut to_visit: VecDeque<String> = ancestors.iter()
+            .filter_map(|class| parents.get(class))
+            .flat_map(|p| p.interfaces.iter().map(|(name, _)| name.clone()))
+            .collect();
+    while let Some(interface) = to_visit.pop_front() {
+        if ancestors.contains(&interface) {
+            continue;
+        }
+        if let Some(p) = parents.get(&interface) {
+            to_visit.extend(p.super_class.iter().chain(p.interfaces.iter()).map(|(name, _)| name.clone()));
+        }
+        ancestors.push(interface);
+    }
+    ancestors
+}
@@ -0,0 +1,242 @@
+extern crate lang_parser;
+
+use lang_parser::ast::*;
+use lang_parser::Span;
+use source_file::SourceFile;
+use ast_actions::ast_visitor::AstVisitor;
+use symbol_table::{GlobalSymbolTable, NSTEntry, CSTEntry, FunctionSymbolTable, ScopeId, Capture, CaptureMode};
+use errors::diagnostic::{Diagnostic, DiagnosticSink};
+use errors::codes::CAPTURED_BEFORE_DECLARATION;
+use errors::classes::class_err::class_member_error;
+use errors::functions::function_err::function_error;
+use std::collections::{HashMap, HashSet};
+
+/**
+ * Captures of the closures of a function, keyed by closure scope, along
+ * with the namespace, the class and the declaration of the function
+ */
+type FunctionCaptures = (String, Option<String>, Span, HashMap<ScopeId, Vec<Capture>>);
+
+/**
+ * Computes what each closure captures from the enclosing scopes of its
+ * function and records it in the closure entry of the symbol table. A
+ * closure nested in another one makes it capture what it takes from outside
+ * both. Closures using a variable declared after them are reported.
+ */
+pub fn collect_captures(namespaces: &mut HashMap<String, Vec<SourceFile>>, gst: &mut GlobalSymbolTable,
+        sink: &mut DiagnosticSink) {
+    let mut captures: Vec<FunctionCaptures> = vec![];
+    {
+        let mut collector = CaptureCollector::new(gst, sink);
+        for source_files in namespaces.values_mut() {
+            for source_file in source_files {
+                collector.visit_file(source_file.get_ast());
+            }
+        }
+        captures.append(&mut collector.captures);
+    }
+    // gst no longer borrowed
+    for (namespace, class, declaration, closures) in captures {
+        if let Some(fst) = function_table_mut(gst, &namespace, class.as_deref(), declaration) {
+            for (closure, closure_captures) in closures {
+                fst.set_captures(closure, closure_captures);
+            }
+        }
+    }
+}
+
+/**
+ * Symbol table of the freestanding function or method declared at
+ * `declaration`
+ */
+pub fn function_table<'a>(gst: &'a GlobalSymbolTable, namespace: &str, class: Option<&str>, declaration: Span)
+        -> Option<&'a FunctionSymbolTable> {
+    let nst = gst.get(namespace)?;
+    match class {
+        Some(class) => match nst.get(class)? {
+            NSTEntry::Class(_, _, _, _, cst) => cst.values().find_map(|entry| match entry {
+                CSTEntry::Method(_, _, _, span, fst) if *span == declaration => Some(fst),
+                _ => None
+            }),
+            NSTEntry::Fun(..) => None
+        },
+        None => nst.values().find_map(|entry| match entry {
+            NSTEntry::Fun(_, _, _, span, fst) if *span == declaration => Some(fst),
+            _ => None
+        }),
+    }
+}
+
+fn function_table_mut<'a>(gst: &'a mut GlobalSymbolTable, namespace: &str, class: Option<&str>, declaration: Span)
+        -> Option<&'a mut FunctionSymbolTable> {
+    let nst = gst.get_mut(namespace)?;
+    match class {
+        Some(class) => match nst.get_mut(class)? {
+            NSTEntry::Class(_, _, _, _, cst) => cst.values_mut().find_map(|entry| match entry {
+                CSTEntry::Method(_, _, _, span, fst) if *span == declaration => Some(fst),
+                _ => None
+            }),
+            NSTEntry::Fun(..) => None
+        },
+        None => nst.values_mut().find_map(|entry| match entry {
+            NSTEntry::Fun(_, _, _, span, fst) if *span == declaration => Some(fst),
+            _ => None
+        }),
+    }
+}
+
+struct CaptureCollector<'a> {
+    gst: &'a GlobalSymbolTable,
+    sink: &'a mut DiagnosticSink,
+    namespace: String,
+    class: Option<String>,
+    function: String,
+    // symbol table and declaration of the function being visited
+    fst: Option<&'a FunctionSymbolTable>,
+    declaration: Option<Span>,
+    // captures of the closures of the function being visited
+    closures: HashMap<ScopeId, Vec<Capture>>,
+    // (closure, name) of the captures already reported as declared after
+    // the closure
+    reported: HashSet<(ScopeId, String)>,
+    captures: Vec<FunctionCaptures>,
+}
+
+impl<'a> CaptureCollector<'a> {
+    pub fn new(gst: &'a GlobalSymbolTable, sink: &'a mut DiagnosticSink) -> Self {
+        CaptureCollector {
+            gst,
+            sink,
+            namespace: String::from(""),
+            class: None,
+            function: String::from(""),
+            fst: None,
+            declaration: None,
+            closures: HashMap::new(),
+            reported: HashSet::new(),
+            captures: vec![],
+        }
+    }
+
+    fn error(&self, code: &str, msg: &str, span: Span) -> Diagnostic {
+        match &self.class {
+            Some(class) => class_member_error(code, msg, &self.namespace, class, &self.function, span),
+            None => function_error(code, msg, &self.namespace, &self.function, span),
+        }
+    }
+
+    /**
+     * Adds a capture to a closure, a mutation taking over a read
+     */
+    fn capture(&mut self, closure: ScopeId, name: &str, declaration: Span, mode: CaptureMode) {
+        let captures = self.closures.entry(closure).or_default();
+        match captures.iter_mut().find(|capture| capture.get_name() == name) {
+            Some(capture) if mode == CaptureMode::Mutate => capture.set_mode(mode),
+            Some(_) => {},
+            None => captures.push(Capture::new(String::from(name), declaration, mode)),
+        }
+    }
+
+    /**
+     * Records a use of `name` at `span`, a capture for each closure between
+     * the use and the declaration of the param or variable it refers to
+     */
+    fn use_name(&mut self, name: &str, span: Span, mode: CaptureMode) {
+        let fst = match self.fst {
+            Some(fst) => fst,
+            None => return
+        };
+        let position = span.get_start_byte();
+        let scope = fst.scope_at(position);
+        if name == "this" || name == "self" || name == "super" {
+            if let (Some(_), Some(declaration)) = (&self.class, self.declaration) {
+                for closure in fst.closures_between(scope, FunctionSymbolTable::ROOT) {
+                    self.capture(closure, "this", declaration, CaptureMode::Read);
+                }
+            }
+            return;
+        }
+        let (declared_in, declaration, is_declared_after) = match fst.lookup_before(scope, name, position) {
+            Some((declared_in, entry)) => (declared_in, entry.get_span(), false),
+            None => match fst.lookup(scope, name) {
+                Some((declared_in, entry)) => (declared_in, entry.get_span(), true),
+                None => return
+            }
+        };
+        let closures = fst.closures_between(scope, declared_in);
+        if let (Some(outermost), true) = (closures.last(), is_declared_after) {
+            if self.reported.insert((*outermost, String::from(name))) {
+                let diagnostic = self.error(CAPTURED_BEFORE_DECLARATION,
+                        &format!("closure captures `{}` before its declaration", name), span)
+                        .with_secondary(declaration, "declared here");
+                self.sink.push(diagnostic);
+            }
+        }
+        for closure in closures {
+            self.capture(closure, name, declaration, mode);
+        }
+    }
+}
+
+impl<'a> AstVisitor for CaptureCollector<'a> {
+    fn visit_file(&mut self, n: &mut File) {
+        self.namespace = String::from(n.get_namespace());
+        for entity in n.get_entities() {
+            match entity {
+                FirstClassEntity::Function(f) => {
+                    self.class = None;
+                    self.visit_function(f);
+                },
+                FirstClassEntity::Class(c) => {
+                    self.class = Some(String::from(c.get_name()));
+                    self.visit_class(c);
+                },
+            }
+        }
+    }
+
+    fn visit_function(&mut self, n: &mut Function) {
+        let declaration = n.get_span();
+        self.function = String::from(n.get_name());
+        self.fst = function_table(self.gst, &self.namespace, self.class.as_deref(), declaration);
+        self.declaration = Some(declaration);
+        self.reported.clear();
+
+        for stmt in n.get_statements() {
+            self.visit_statement(stmt);
+        }
+
+        let closures = std::mem::take(&mut self.closures);
+        if !closures.is_empty() {
+            self.captures.push((self.namespace.clone(), self.class.clone(), declaration, closures));
+        }
+        self.fst = None;
+    }
+
+    fn visit_qualified_expression(&mut self, n: &mut QualifiedExpression) {
+        for (index, part) in n.get_parts().iter_mut().enumerate() {
+            match (index, part) {
+                (0, QualifiedExpressionPart::Identifier(i)) => {
+                    self.use_name(i.get_name(), i.get_span(), CaptureMode::Read);
+                },
+                // closures stored in variables are called by name
+                (0, QualifiedExpressionPart::MethodCall(call)) => {
+                    self.use_name(call.get_name(), call.get_span(), CaptureMode::Read);
+                    self.visit_function_call(call);
+                },
+                (_, part) => self.visit_qualified_expression_part(part),
+            }
+        }
+    }
+
+    fn visit_variable_affectation(&mut self, n: &mut VariableAffectation) {
+        let receiver = n.get_receiver();
+        match receiver.get_parts().as_mut_slice() {
+            [QualifiedExpressionPart::Identifier(i)] => {
+                self.use_name(i.get_name(), i.get_span(), CaptureMode::Mutate);
+            },
+            _ => self.visit_qualified_expression(receiver),
+        }
+        self.visit_expression(n.get_value());
+    }
+}
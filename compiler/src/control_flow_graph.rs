@@ -0,0 +1,612 @@
+extern crate lang_parser;
+
+use lang_parser::ast::*;
+use lang_parser::Span;
+use source_file::SourceFile;
+use ast_actions::ast_visitor::AstVisitor;
+use ast_actions::validators::flow_checker::is_always_true;
+use symbol_table::{GlobalSymbolTable, FunctionSymbolTable, mangle, closure_name};
+use closure_captures::function_table;
+use std::fmt;
+
+pub type BlockId = usize;
+
+/**
+ * Kind of the transfer of control along an edge
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EdgeKind {
+    Jump,
+    // the condition ending the source block holds, or not
+    True,
+    False,
+    // from the end of a loop body back to its condition
+    Back,
+    Break,
+    Continue,
+    Return,
+}
+
+#[derive(Debug)]
+pub struct Edge {
+    target: BlockId,
+    kind: EdgeKind,
+}
+
+/**
+ * Simple statements run in sequence, possibly ending with the condition of
+ * an `if` or a loop deciding which successor follows
+ */
+#[derive(Debug)]
+pub enum Instruction {
+    Statement(Statement),
+    Condition(Expression),
+}
+
+#[derive(Debug)]
+pub struct BasicBlock {
+    id: BlockId,
+    instructions: Vec<Instruction>,
+    successors: Vec<Edge>,
+    predecessors: Vec<BlockId>,
+}
+
+/**
+ * Control flow graph of a function or closure body. Control enters at the
+ * ENTRY block and leaves through the EXIT block, which has no instructions
+ */
+#[derive(Debug)]
+pub struct ControlFlowGraph {
+    name: String,
+    blocks: Vec<BasicBlock>,
+}
+
+impl Edge {
+    pub fn get_target(&self) -> BlockId {
+        self.target
+    }
+
+    pub fn get_kind(&self) -> EdgeKind {
+        self.kind
+    }
+}
+
+impl BasicBlock {
+    pub fn get_id(&self) -> BlockId {
+        self.id
+    }
+
+    pub fn get_instructions(&self) -> &Vec<Instruction> {
+        &self.instructions
+    }
+
+    pub fn get_successors(&self) -> &Vec<Edge> {
+        &self.successors
+    }
+
+    pub fn get_predecessors(&self) -> &Vec<BlockId> {
+        &self.predecessors
+    }
+}
+
+impl Instruction {
+    pub fn get_span(&self) -> Span {
+        match self {
+            Instruction::Statement(s) => s.get_span(),
+            Instruction::Condition(e) => e.get_span(),
+        }
+    }
+}
+
+impl fmt::Display for EdgeKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            EdgeKind::Jump => "jump",
+            EdgeKind::True => "true",
+            EdgeKind::False => "false",
+            EdgeKind::Back => "back",
+            EdgeKind::Break => "break",
+            EdgeKind::Continue => "continue",
+            EdgeKind::Return => "return",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl ControlFlowGraph {
+    pub const ENTRY: BlockId = 0;
+    pub const EXIT: BlockId = 1;
+
+    pub fn new(name: String) -> Self {
+        let mut graph = ControlFlowGraph {
+            name,
+            blocks: vec![],
+        };
+        graph.add_block();
+        graph.add_block();
+        graph
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_blocks(&self) -> &Vec<BasicBlock> {
+        &self.blocks
+    }
+
+    pub fn get_block(&self, id: BlockId) -> &BasicBlock {
+        &self.blocks[id]
+    }
+
+    pub fn add_block(&mut self) -> BlockId {
+        let id = self.blocks.len();
+        self.blocks.push(BasicBlock {
+            id,
+            instructions: vec![],
+            successors: vec![],
+            predecessors: vec![],
+        });
+        id
+    }
+
+    pub fn add_edge(&mut self, source: BlockId, target: BlockId, kind: EdgeKind) {
+        self.blocks[source].successors.push(Edge { target, kind });
+        self.blocks[target].predecessors.push(source);
+    }
+
+    /**
+     * Blocks reachable from the entry, each one before its successors
+     * except along back edges
+     */
+    pub fn reverse_postorder(&self) -> Vec<BlockId> {
+        let mut visited = vec![false; self.blocks.len()];
+        let mut postorder: Vec<BlockId> = vec![];
+        // blocks with the index of their next successor to visit
+        let mut stack: Vec<(BlockId, usize)> = vec![(ControlFlowGraph::ENTRY, 0)];
+        visited[ControlFlowGraph::ENTRY] = true;
+        while let Some((block, next)) = stack.pop() {
+            match self.blocks[block].successors.get(next) {
+                Some(edge) => {
+                    stack.push((block, next + 1));
+                    if !visited[edge.target] {
+                        visited[edge.target] = true;
+                        stack.push((edge.target, 0));
+                    }
+                },
+                None => postorder.push(block),
+            }
+        }
+        postorder.reverse();
+        postorder
+    }
+
+    /**
+     * Immediate dominator of each block: the closest block that control
+     * goes through on every path from the entry. None for the entry and
+     * for the blocks that cannot be reached.
+     * Iterative algorithm from Cooper, Harvey and Kennedy: "A Simple, Fast
+     * Dominance Algorithm"
+     */
+    pub fn immediate_dominators(&self) -> Vec<Option<BlockId>> {
+        let order = self.reverse_postorder();
+        let mut positions = vec![usize::MAX; self.blocks.len()];
+        for (position, block) in order.iter().enumerate() {
+            positions[*block] = position;
+        }
+        let mut dominators: Vec<Option<BlockId>> = vec![None; self.blocks.len()];
+        dominators[ControlFlowGraph::ENTRY] = Some(ControlFlowGraph::ENTRY);
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for block in order.iter().skip(1) {
+                let mut dominator: Option<BlockId> = None;
+                for predecessor in self.get_block(*block).get_predecessors() {
+                    if dominators[*predecessor].is_none() {
+                        continue;
+                    }
+                    dominator = match dominator {
+                        Some(d) => Some(intersect(&dominators, &positions, d, *predecessor)),
+                        None => Some(*predecessor),
+                    };
+                }
+                if dominator != dominators[*block] {
+                    dominators[*block] = dominator;
+                    changed = true;
+                }
+            }
+        }
+        dominators[ControlFlowGraph::ENTRY] = None;
+        dominators
+    }
+
+    /**
+     * Graphviz description of the graph, the instructions are printed as
+     * written in `code` and each block names its immediate dominator
+     */
+    pub fn to_dot(&self, code: &str) -> String {
+        let dominators = self.immediate_dominators();
+        let mut dot = format!("digraph \"{}\" {{\n", escape(self.get_name()));
+        dot.push_str("    node [shape=box, fontname=\"monospace\"];\n");
+        for block in self.get_blocks() {
+            let mut label = format!("B{}", block.get_id());
+            match (block.get_id(), dominators[block.get_id()]) {
+                (ControlFlowGraph::ENTRY, _) => label.push_str(" entry"),
+                (ControlFlowGraph::EXIT, Some(d)) => label.push_str(&format!(" exit (idom B{})", d)),
+                (ControlFlowGraph::EXIT, None) => label.push_str(" exit (unreachable)"),
+                (_, Some(d)) => label.push_str(&format!(" (idom B{})", d)),
+                (_, None) => label.push_str(" (unreachable)"),
+            }
+            label = escape(&label);
+            label.push_str("\\l");
+            for instruction in block.get_instructions() {
+                let span = instruction.get_span();
+                let text = code[span.get_start_byte()..span.get_end_byte()].split_whitespace().collect::<Vec<&str>>().join(" ");
+                let text = match instruction {
+                    Instruction::Condition(_) => format!("branch {}", text),
+                    Instruction::Statement(_) => text,
+                };
+                label.push_str(&escape(&text));
+                label.push_str("\\l");
+            }
+            dot.push_str(&format!("    B{} [label=\"{}\"];\n", block.get_id(), label));
+        }
+        for block in self.get_blocks() {
+            for edge in block.get_successors() {
+                dot.push_str(&format!("    B{} -> B{} [label=\"{}\"];\n", block.get_id(), edge.get_target(), edge.get_kind()));
+            }
+        }
+        dot.push('}');
+        dot
+    }
+}
+
+/**
+ * Closest common dominator of two blocks whose dominators are known,
+ * walking up the dominator tree by reverse postorder position
+ */
+fn intersect(dominators: &[Option<BlockId>], positions: &[usize], a: BlockId, b: BlockId) -> BlockId {
+    let (mut a, mut b) = (a, b);
+    while a != b {
+        while positions[a] > positions[b] {
+            a = dominators[a].unwrap();
+        }
+        while positions[b] > positions[a] {
+            b = dominators[b].unwrap();
+        }
+    }
+    a
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/**
+ * Builds the control flow graphs of the function and closure bodies of a
+ * file, each graph followed by the ones of its closures
+ */
+pub fn build_graphs(s: &mut SourceFile, gst: &GlobalSymbolTable) -> Vec<ControlFlowGraph> {
+    let mut builder = GraphBuilder::new(gst);
+    builder.visit_file(s.get_ast());
+    builder.graphs
+}
+
+/**
+ * Blocks ending with an edge of the given kind to a block not created yet
+ */
+type PendingEdges = Vec<(BlockId, EdgeKind)>;
+
+/**
+ * Blocks leaving the innermost loop being built through `break` and
+ * `continue`, linked once the loop is complete
+ */
+struct LoopExits {
+    breaks: Vec<BlockId>,
+    continues: Vec<BlockId>,
+}
+
+struct GraphBuilder<'a> {
+    gst: &'a GlobalSymbolTable,
+    namespace: String,
+    class: Option<String>,
+    // name of the function being built, closures are named after it and
+    // their scope in its symbol table
+    function: String,
+    fst: Option<&'a FunctionSymbolTable>,
+    graph: ControlFlowGraph,
+    // block the next instruction goes to, None after a jump until the
+    // next instruction, which then starts an unreachable block
+    current: Option<BlockId>,
+    loops: Vec<LoopExits>,
+    graphs: Vec<ControlFlowGraph>,
+}
+
+impl<'a> GraphBuilder<'a> {
+    pub fn new(gst: &'a GlobalSymbolTable) -> Self {
+        GraphBuilder {
+            gst,
+            namespace: String::from(""),
+            class: None,
+            function: String::from(""),
+            fst: None,
+            graph: ControlFlowGraph::new(String::from("")),
+            current: None,
+            loops: vec![],
+            graphs: vec![],
+        }
+    }
+
+    fn current_block(&mut self) -> BlockId {
+        match self.current {
+            Some(block) => block,
+            None => {
+                let block = self.graph.add_block();
+                self.current = Some(block);
+                block
+            }
+        }
+    }
+
+    fn push(&mut self, instruction: Instruction) {
+        let block = self.current_block();
+        self.graph.blocks[block].instructions.push(instruction);
+    }
+
+    /**
+     * Ends the current block with an edge to `target`, nothing follows it
+     * until the next instruction
+     */
+    fn jump(&mut self, target: BlockId, kind: EdgeKind) {
+        if let Some(block) = self.current.take() {
+            self.graph.add_edge(block, target, kind);
+        }
+    }
+
+    /**
+     * Starts a new block reached from `source`
+     */
+    fn start_block(&mut self, source: BlockId, kind: EdgeKind) {
+        let block = self.graph.add_block();
+        self.graph.add_edge(source, block, kind);
+        self.current = Some(block);
+    }
+
+    /**
+     * New block reached from each of `sources`, if any
+     */
+    fn join(&mut self, sources: &[(BlockId, EdgeKind)]) -> Option<BlockId> {
+        if sources.is_empty() {
+            return None;
+        }
+        let block = self.graph.add_block();
+        for (source, kind) in sources {
+            self.graph.add_edge(*source, block, *kind);
+        }
+        Some(block)
+    }
+
+    /**
+     * Builds the graph of a body and adds it, before the graphs of its
+     * closures
+     */
+    fn build(&mut self, name: String, statements: &mut Vec<Statement>) {
+        let index = self.graphs.len();
+        let outer_graph = std::mem::replace(&mut self.graph, ControlFlowGraph::new(name));
+        let outer_current = self.current.replace(ControlFlowGraph::ENTRY);
+        let outer_loops = std::mem::take(&mut self.loops);
+
+        for stmt in statements {
+            self.visit_statement(stmt);
+        }
+        self.jump(ControlFlowGraph::EXIT, EdgeKind::Jump);
+
+        let graph = std::mem::replace(&mut self.graph, outer_graph);
+        self.graphs.insert(index, graph);
+        self.current = outer_current;
+        self.loops = outer_loops;
+    }
+
+    /**
+     * Builds a loop body entered from the current block, `condition` being
+     * the block the control goes back to. Gives the blocks that may fall
+     * through the end of the body or continue, and the ones that break
+     */
+    fn build_loop_body(&mut self, statements: &mut Vec<Statement>) -> (PendingEdges, PendingEdges) {
+        self.loops.push(LoopExits {
+            breaks: vec![],
+            continues: vec![],
+        });
+        for stmt in statements {
+            self.visit_statement(stmt);
+        }
+        let exits = self.loops.pop().unwrap();
+        let mut ends: PendingEdges = self.current.take().map(|b| (b, EdgeKind::Back)).into_iter().collect();
+        ends.extend(exits.continues.into_iter().map(|b| (b, EdgeKind::Continue)));
+        let breaks = exits.breaks.into_iter().map(|b| (b, EdgeKind::Break)).collect();
+
+        (ends, breaks)
+    }
+}
+
+impl<'a> AstVisitor for GraphBuilder<'a> {
+    fn visit_file(&mut self, n: &mut File) {
+        self.namespace = String::from(n.get_namespace());
+        for entity in n.get_entities() {
+            match entity {
+                FirstClassEntity::Function(f) => {
+                    self.class = None;
+                    self.visit_function(f);
+                },
+                FirstClassEntity::Class(c) => {
+                    self.class = Some(String::from(c.get_name()));
+                    self.visit_class(c);
+                },
+            }
+        }
+    }
+
+    fn visit_function(&mut self, n: &mut Function) {
+        if !n.has_body() {
+            return;
+        }
+        self.function = match &self.class {
+            Some(class) => {
+                let param_types: Vec<TypeRef> = n.get_params().iter_mut().map(|p| p.get_type().clone()).collect();
+                let name = String::from(n.get_name());
+                let method = mangle(&name, n.get_type_params(), &param_types);
+                format!("{}.{}.{}", self.namespace, class, method)
+            },
+            None => format!("{}.{}", self.namespace, n.get_name()),
+        };
+        self.fst = function_table(self.gst, &self.namespace, self.class.as_deref(), n.get_span());
+        let name = self.function.clone();
+        self.build(name, n.get_statements());
+        self.fst = None;
+    }
+
+    fn visit_closure(&mut self, n: &mut Closure) {
+        // redefined functions are left out of the symbol table, they stop
+        // the compilation before the graphs are built
+        if let Some(fst) = self.fst {
+            let scope = fst.scope_at(n.get_span().get_start_byte());
+            let name = format!("{}.{}", self.function, closure_name(scope));
+            self.build(name, n.get_statements());
+        }
+    }
+
+    fn visit_statement(&mut self, n: &mut Statement) {
+        match n {
+            Statement::ReturnStatement { expr, span: _ } => {
+                if let Some(e) = expr {
+                    self.visit_expression(e);
+                }
+                self.push(Instruction::Statement(n.clone()));
+                self.jump(ControlFlowGraph::EXIT, EdgeKind::Return);
+            },
+            Statement::Declaration(d) => {
+                self.visit_variable_declaration(d);
+                self.push(Instruction::Statement(n.clone()));
+            },
+            Statement::Affectation(a) => {
+                self.visit_variable_affectation(a);
+                self.push(Instruction::Statement(n.clone()));
+            },
+            Statement::QualifiedExpression(q) => {
+                self.visit_qualified_expression(q);
+                self.push(Instruction::Statement(n.clone()));
+            },
+            Statement::If(i) => {
+                self.visit_if_statement(i);
+            },
+            Statement::While(w) => {
+                self.visit_while_statement(w);
+            },
+            Statement::For(f) => {
+                self.visit_for_statement(f);
+            },
+            // a `break` or `continue` outside of a loop, reported by the flow
+            // checker, only ends the block
+            Statement::Break { span: _ } => {
+                let block = self.current.take();
+                if let Some(exits) = self.loops.last_mut() {
+                    exits.breaks.extend(block);
+                }
+            },
+            Statement::Continue { span: _ } => {
+                let block = self.current.take();
+                if let Some(exits) = self.loops.last_mut() {
+                    exits.continues.extend(block);
+                }
+            },
+        }
+    }
+
+    fn visit_if_statement(&mut self, n: &mut IfStatement) {
+        self.visit_expression(n.get_condition());
+        self.push(Instruction::Condition(n.get_condition().clone()));
+        let condition = self.current_block();
+        let mut ends: PendingEdges = vec![];
+
+        self.start_block(condition, EdgeKind::True);
+        for stmt in n.get_statements() {
+            self.visit_statement(stmt);
+        }
+        ends.extend(self.current.take().map(|b| (b, EdgeKind::Jump)));
+
+        match n.get_else_statements() {
+            Some(else_statements) => {
+                self.start_block(condition, EdgeKind::False);
+                for stmt in else_statements {
+                    self.visit_statement(stmt);
+                }
+                ends.extend(self.current.take().map(|b| (b, EdgeKind::Jump)));
+            },
+            None => ends.push((condition, EdgeKind::False)),
+        }
+        self.current = self.join(&ends);
+    }
+
+    fn visit_while_statement(&mut self, n: &mut WhileStatement) {
+        let header = self.graph.add_block();
+        self.jump(header, EdgeKind::Jump);
+        self.current = Some(header);
+        self.visit_expression(n.get_condition());
+        self.push(Instruction::Condition(n.get_condition().clone()));
+        // `while (true)` is only left through `break`
+        let is_infinite = is_always_true(n.get_condition());
+
+        self.start_block(header, EdgeKind::True);
+        let (ends, mut breaks) = self.build_loop_body(n.get_statements());
+        for (block, kind) in ends {
+            self.graph.add_edge(block, header, kind);
+        }
+        if !is_infinite {
+            breaks.insert(0, (header, EdgeKind::False));
+        }
+        self.current = self.join(&breaks);
+    }
+
+    fn visit_for_statement(&mut self, n: &mut ForStatement) {
+        if let Some(init) = n.get_init() {
+            self.visit_statement(init);
+        }
+        let header = self.graph.add_block();
+        self.jump(header, EdgeKind::Jump);
+        self.current = Some(header);
+        let (body_edge, is_infinite) = match n.get_condition() {
+            Some(condition) => {
+                self.visit_expression(condition);
+                self.push(Instruction::Condition(condition.clone()));
+                (EdgeKind::True, is_always_true(condition))
+            },
+            // no condition, the loop is only left through `break`
+            None => (EdgeKind::Jump, true)
+        };
+
+        self.start_block(header, body_edge);
+        let (ends, mut breaks) = self.build_loop_body(n.get_statements());
+        match n.get_update() {
+            Some(update) => {
+                let ends: PendingEdges = ends.into_iter()
+                        .map(|(b, kind)| (b, if kind == EdgeKind::Back { EdgeKind::Jump } else { kind }))
+                        .collect();
+                self.current = self.join(&ends);
+                if self.current.is_some() {
+                    self.visit_statement(update);
+                    self.jump(header, EdgeKind::Back);
+                }
+            },
+            None => {
+                for (block, kind) in ends {
+                    self.graph.add_edge(block, header, kind);
+                }
+            }
+        }
+        if !is_infinite {
+            breaks.insert(0, (header, EdgeKind::False));
+        }
+        self.current = self.join(&breaks);
+    }
+}
@@ -1,13 +1,19 @@
-use std::process::exit;
+extern crate lang_parser;
 
-pub fn class_error(msg: &str, namespace: &str, class: &str, file: &str, pos: (usize, usize)) {
-    let (line, col) = pos;
-    println!("[Error] Class {}.{}: {}\n{}: {},{}\n", namespace, class, msg, file, line, col);
-    exit(-1);
+use lang_parser::Span;
+use errors::diagnostic::Diagnostic;
+
+pub fn class_error(code: &str, msg: &str, namespace: &str, class: &str, span: Span) -> Diagnostic {
+    Diagnostic::error(code, format!("Class {}.{}: {}", namespace, class, msg))
+            .with_primary(span, "")
+}
+
+pub fn class_member_error(code: &str, msg: &str, namespace: &str, class: &str, member: &str, span: Span) -> Diagnostic {
+    Diagnostic::error(code, format!("Class member {}.{}.{}: {}", namespace, class, member, msg))
+            .with_primary(span, "")
 }
 
-pub fn class_member_error(msg: &str, namespace: &str, class: &str, member: &str, file: &str, pos: (usize, usize)) {
-    let (line, col) = pos;
-    println!("[Error] Class member {}.{}.{}: {}\n{}: {},{}\n", namespace, class, member, msg, file, line, col);
-    exit(-1);
-}
\ No newline at end of file
+pub fn class_member_warning(code: &str, msg: &str, namespace: &str, class: &str, member: &str, span: Span) -> Diagnostic {
+    Diagnostic::warning(code, format!("Class member {}.{}.{}: {}", namespace, class, member, msg))
+            .with_primary(span, "")
+}
@@ -0,0 +1,73 @@
+// Error codes of the diagnostics, grouped by the phase reporting them
+
+// files
+pub const FILE_NOT_FOUND: &str = "E0001";
+pub const SYNTAX_ERROR: &str = "E0002";
+
+// classes and methods attributes
+pub const ABSTRACT_METHOD_IN_CONCRETE_CLASS: &str = "E0101";
+pub const BODYLESS_METHOD: &str = "E0103";
+pub const INTERFACE_METHOD_BODY: &str = "E0104";
+pub const NATIVE_METHOD_BODY: &str = "E0105";
+pub const ABSTRACT_METHOD_BODY: &str = "E0106";
+
+// freestanding functions attributes
+pub const NATIVE_FUNCTION_BODY: &str = "E0201";
+pub const BODYLESS_FUNCTION: &str = "E0202";
+
+// closures
+pub const CLOSURE_TYPE_MISMATCH: &str = "E0300";
+pub const CAPTURED_BEFORE_DECLARATION: &str = "E0301";
+
+// indexing
+pub const NOT_INDEXABLE: &str = "E0400";
+pub const INDEX_NOT_ASSIGNABLE: &str = "E0401";
+
+// attributes
+pub const UNKNOWN_ATTRIBUTE: &str = "E0500";
+pub const MISPLACED_ATTRIBUTE: &str = "E0501";
+pub const CONFLICTING_ATTRIBUTES: &str = "E0502";
+pub const REPEATED_ATTRIBUTE: &str = "E0503";
+
+// types
+pub const MISMATCHED_TYPES: &str = "E0600";
+pub const INVALID_OPERAND_TYPES: &str = "E0601";
+pub const WRONG_ARGUMENTS_COUNT: &str = "E0602";
+pub const MISSING_RETURN_VALUE: &str = "E0603";
+
+// names
+pub const UNRESOLVED_NAME: &str = "E0700";
+pub const UNKNOWN_MEMBER: &str = "E0701";
+pub const USED_BEFORE_DECLARATION: &str = "E0702";
+
+// imports
+pub const UNKNOWN_IMPORT: &str = "E0800";
+pub const AMBIGUOUS_NAME: &str = "E0801";
+pub const IMPORT_CYCLE: &str = "E0802";
+pub const INVALID_IMPORT_ALIAS: &str = "E0803";
+pub const UNUSED_IMPORT: &str = "W0800";
+
+// class hierarchy
+pub const UNKNOWN_SUPERTYPE: &str = "E0900";
+pub const INVALID_SUPERCLASS: &str = "E0901";
+pub const NOT_AN_INTERFACE: &str = "E0902";
+pub const INHERITANCE_CYCLE: &str = "E0903";
+
+// implementations
+pub const MISSING_IMPLEMENTATION: &str = "E1000";
+pub const INCOMPATIBLE_OVERRIDE: &str = "E1001";
+
+// definitions
+pub const DUPLICATE_DEFINITION: &str = "E1100";
+pub const SHADOWED_NAME: &str = "W1100";
+
+// overloads
+pub const AMBIGUOUS_CALL: &str = "E1200";
+pub const NO_MATCHING_OVERLOAD: &str = "E1201";
+
+// control flow
+pub const MISSING_RETURN: &str = "E1300";
+pub const VOID_RETURN_VALUE: &str = "E1301";
+pub const POSSIBLY_UNASSIGNED: &str = "E1302";
+pub const JUMP_OUTSIDE_LOOP: &str = "E1303";
+pub const UNREACHABLE_CODE: &str = "W1300";
@@ -0,0 +1,212 @@
+extern crate lang_parser;
+
+use lang_parser::Span;
+use source_map::SourceMap;
+use errors::{human, json, sarif};
+use std::io::{stderr, IsTerminal};
+use std::fs;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/**
+ * A span of the source code with a message explaining its part in the
+ * diagnostic, the message can be empty
+ */
+#[derive(Clone, Debug)]
+pub struct Label {
+    span: Span,
+    message: String,
+}
+
+/**
+ * A problem found in the source code.
+ * The primary label points at the code to fix, the secondary labels at the
+ * code related to it, such as a previous declaration.
+ */
+#[derive(Clone, Debug)]
+pub struct Diagnostic {
+    severity: Severity,
+    code: String,
+    message: String,
+    primary: Option<Label>,
+    secondary: Vec<Label>,
+    help: Option<String>,
+}
+
+/**
+ * Collects the diagnostics reported during a compilation phase, they are
+ * all printed at the end of the phase
+ */
+#[derive(Debug)]
+pub struct DiagnosticSink {
+    format: ErrorFormat,
+    diagnostics: Vec<Diagnostic>,
+    // diagnostics of the previous phases, kept for the SARIF log
+    flushed: Vec<Diagnostic>,
+    // file the SARIF log is written to
+    sarif_output: String,
+}
+
+/**
+ * How the diagnostics are written, chosen with `--error-format`
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorFormat {
+    Human,
+    // one JSON object per line
+    Json,
+    // a single SARIF log written to a file once the compilation is over
+    Sarif,
+}
+
+impl Severity {
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        }
+    }
+}
+
+impl ErrorFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "human" => Some(ErrorFormat::Human),
+            "json" => Some(ErrorFormat::Json),
+            "sarif" => Some(ErrorFormat::Sarif),
+            _ => None
+        }
+    }
+}
+
+impl Label {
+    pub fn get_span(&self) -> Span {
+        self.span
+    }
+
+    pub fn get_message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn new(span: Span, message: String) -> Self {
+        Label {
+            span,
+            message,
+        }
+    }
+}
+
+impl Diagnostic {
+    pub fn get_severity(&self) -> Severity {
+        self.severity
+    }
+
+    pub fn get_code(&self) -> &str {
+        &self.code
+    }
+
+    pub fn get_message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn get_primary(&self) -> Option<&Label> {
+        self.primary.as_ref()
+    }
+
+    pub fn get_secondary(&self) -> &Vec<Label> {
+        &self.secondary
+    }
+
+    pub fn get_help(&self) -> Option<&str> {
+        self.help.as_deref()
+    }
+
+    pub fn new(severity: Severity, code: &str, message: String) -> Self {
+        Diagnostic {
+            severity,
+            code: String::from(code),
+            message,
+            primary: None,
+            secondary: vec![],
+            help: None,
+        }
+    }
+
+    pub fn error(code: &str, message: String) -> Self {
+        Diagnostic::new(Severity::Error, code, message)
+    }
+
+    pub fn warning(code: &str, message: String) -> Self {
+        Diagnostic::new(Severity::Warning, code, message)
+    }
+
+    pub fn with_primary(mut self, span: Span, message: &str) -> Self {
+        self.primary = Some(Label::new(span, String::from(message)));
+        self
+    }
+
+    pub fn with_secondary(mut self, span: Span, message: &str) -> Self {
+        self.secondary.push(Label::new(span, String::from(message)));
+        self
+    }
+
+    pub fn with_help(mut self, help: &str) -> Self {
+        self.help = Some(String::from(help));
+        self
+    }
+}
+
+impl DiagnosticSink {
+    pub fn new(format: ErrorFormat, sarif_output: &str) -> Self {
+        DiagnosticSink {
+            format,
+            diagnostics: vec![],
+            flushed: vec![],
+            sarif_output: String::from(sarif_output),
+        }
+    }
+
+    pub fn push(&mut self, diagnostic: Diagnostic) {
+        self.diagnostics.push(diagnostic);
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics.iter().any(|d| d.get_severity() == Severity::Error)
+    }
+
+    /**
+     * Prints the collected diagnostics to stderr and forgets them, returns
+     * whether there was an error among them.
+     * Colours are only used when stderr is a terminal.
+     */
+    pub fn flush(&mut self, sources: &SourceMap) -> bool {
+        let has_errors = self.has_errors();
+        let colored = stderr().is_terminal();
+        for diagnostic in self.diagnostics.drain(..) {
+            match self.format {
+                ErrorFormat::Human => eprintln!("{}", human::render(&diagnostic, sources, colored)),
+                ErrorFormat::Json => eprintln!("{}", json::render(&diagnostic, sources)),
+                ErrorFormat::Sarif => {},
+            }
+            self.flushed.push(diagnostic);
+        }
+        has_errors
+    }
+
+    /**
+     * Writes what has to be written once the compilation is over, that is
+     * the SARIF log, to its own file
+     */
+    pub fn finish(&mut self, sources: &SourceMap) {
+        self.flush(sources);
+        if self.format == ErrorFormat::Sarif {
+            if let Err(e) = fs::write(&self.sarif_output, sarif::render(&self.flushed, sources)) {
+                eprintln!("error: cannot write the SARIF log to `{}`: {}", self.sarif_output, e);
+            }
+        }
+    }
+}
@@ -1,6 +1,14 @@
-use std::process::exit;
+extern crate lang_parser;
 
-pub fn no_file_error(file: &str) {
-    println!("[Error] File not found: {}", file);
-    exit(-1);
-}
\ No newline at end of file
+use lang_parser::ParseDiagnostic;
+use errors::codes::{FILE_NOT_FOUND, SYNTAX_ERROR};
+use errors::diagnostic::Diagnostic;
+
+pub fn no_file_error(file: &str) -> Diagnostic {
+    Diagnostic::error(FILE_NOT_FOUND, format!("File not found: {}", file))
+}
+
+pub fn parse_error(diagnostic: &ParseDiagnostic) -> Diagnostic {
+    Diagnostic::error(SYNTAX_ERROR, format!("Syntax error: {}", diagnostic.get_message()))
+            .with_primary(diagnostic.get_span(), "")
+}
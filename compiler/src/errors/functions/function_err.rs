@@ -1,7 +1,14 @@
-use std::process::exit;
+extern crate lang_parser;
 
-pub fn function_error(msg: &str, namespace: &str, function: &str, file: &str, pos: (usize, usize)) {
-    let (line, col) = pos;
-    println!("[Error] Function {}.{}: {}\n{}: {},{}\n", namespace, function, msg, file, line, col);
-    exit(-1);
+use lang_parser::Span;
+use errors::diagnostic::Diagnostic;
+
+pub fn function_error(code: &str, msg: &str, namespace: &str, function: &str, span: Span) -> Diagnostic {
+    Diagnostic::error(code, format!("Function {}.{}: {}", namespace, function, msg))
+            .with_primary(span, "")
+}
+
+pub fn function_warning(code: &str, msg: &str, namespace: &str, function: &str, span: Span) -> Diagnostic {
+    Diagnostic::warning(code, format!("Function {}.{}: {}", namespace, function, msg))
+            .with_primary(span, "")
 }
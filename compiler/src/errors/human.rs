@@ -0,0 +1,142 @@
+extern crate lang_parser;
+
+use lang_parser::LineIndex;
+use errors::diagnostic::{Diagnostic, Label, Severity};
+use source_map::SourceMap;
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const RED: &str = "\x1b[1;31m";
+const YELLOW: &str = "\x1b[1;33m";
+const BLUE: &str = "\x1b[1;34m";
+
+/**
+ * Tabs are shown as this many spaces so that underlines stay aligned
+ */
+const TAB_WIDTH: usize = 4;
+
+/**
+ * Renders a diagnostic the way rustc does: a header with the code and the
+ * message, then the source lines of the labels with their spans underlined,
+ * `^` for the primary label and `-` for the secondary ones
+ */
+pub fn render(diagnostic: &Diagnostic, sources: &SourceMap, colored: bool) -> String {
+    let paint = |style: &str, text: &str| {
+        if colored {
+            format!("{}{}{}", style, text, RESET)
+        } else {
+            String::from(text)
+        }
+    };
+    let severity_style = match diagnostic.get_severity() {
+        Severity::Error => RED,
+        Severity::Warning => YELLOW,
+    };
+
+    let mut text = paint(severity_style, &format!("{}[{}]", diagnostic.get_severity().get_name(),
+            diagnostic.get_code()));
+    text.push_str(&paint(BOLD, &format!(": {}", diagnostic.get_message())));
+    text.push('\n');
+
+    // labels are shown in the order of their lines, grouped by file
+    let mut labels: Vec<(&Label, bool)> = vec![];
+    if let Some(primary) = diagnostic.get_primary() {
+        labels.push((primary, true));
+    }
+    for label in diagnostic.get_secondary() {
+        labels.push((label, false));
+    }
+    let gutter_width = labels.iter()
+            .map(|(label, _)| {
+                let span = label.get_span();
+                sources.get_lines(span.get_file_id()).start_of(&span).0.to_string().len()
+            })
+            .max()
+            .unwrap_or(0);
+    let gutter = " ".repeat(gutter_width);
+
+    let mut file_ids: Vec<usize> = vec![];
+    for (label, _) in &labels {
+        let file_id = label.get_span().get_file_id();
+        if !file_ids.contains(&file_id) {
+            file_ids.push(file_id);
+        }
+    }
+    for (i, &file_id) in file_ids.iter().enumerate() {
+        let lines = sources.get_lines(file_id);
+        let mut file_labels: Vec<&(&Label, bool)> = labels.iter()
+                .filter(|(label, _)| label.get_span().get_file_id() == file_id)
+                .collect();
+        file_labels.sort_by_key(|(label, _)| label.get_span().get_start_byte());
+
+        // the location shown is the one of the primary label if it is in
+        // this file
+        let shown = match file_labels.iter().find(|(_, is_primary)| *is_primary) {
+            Some((label, _)) => label.get_span(),
+            None => file_labels[0].0.get_span(),
+        };
+        let (line, col) = lines.start_of(&shown);
+        let arrow = if i == 0 { "-->" } else { ":::" };
+        text.push_str(&format!("{}{} {}:{}:{}\n", gutter, paint(BLUE, arrow),
+                sources.get_path(file_id), line, col));
+        text.push_str(&format!("{} {}\n", gutter, paint(BLUE, "|")));
+
+        let mut previous_line: Option<usize> = None;
+        for (label, is_primary) in file_labels {
+            let (line, start_col, end_col) = underlined_columns(lines, label);
+            if let Some(previous) = previous_line {
+                if line > previous + 1 {
+                    text.push_str(&format!("{}\n", paint(BLUE, "...")));
+                }
+            }
+            if previous_line != Some(line) {
+                let number = format!("{:>width$}", line, width = gutter_width);
+                text.push_str(&format!("{} {} {}\n", paint(BLUE, &number), paint(BLUE, "|"),
+                        expand_tabs(lines.get_line(line))));
+            }
+            let (marker, style) = if *is_primary { ("^", severity_style) } else { ("-", BLUE) };
+            let mut underline = format!("{}{}", " ".repeat(start_col), marker.repeat(end_col - start_col));
+            if !label.get_message().is_empty() {
+                underline.push(' ');
+                underline.push_str(label.get_message());
+            }
+            text.push_str(&format!("{} {} {}\n", gutter, paint(BLUE, "|"), paint(style, &underline)));
+            previous_line = Some(line);
+        }
+    }
+
+    if let Some(help) = diagnostic.get_help() {
+        text.push_str(&format!("{} {} {}\n", gutter, paint(BLUE, "="), paint(BOLD, &format!("help: {}", help))));
+    }
+    text
+}
+
+/**
+ * Line of the start of the label and the columns to underline on it,
+ * counted on the line with its tabs expanded. A span covering several lines
+ * is underlined up to the end of its first line.
+ */
+fn underlined_columns(lines: &LineIndex, label: &Label) -> (usize, usize, usize) {
+    let span = label.get_span();
+    let (line, _) = lines.start_of(&span);
+    let (end_line, _) = lines.line_col(span.get_end_byte());
+    let text = lines.get_line(line);
+    let line_start = lines.line_start(line);
+
+    let start = display_width(&text[..span.get_start_byte() - line_start]);
+    let end = if end_line == line {
+        display_width(&text[..(span.get_end_byte() - line_start).min(text.len())])
+    } else {
+        display_width(text.trim_end())
+    };
+    // an empty span is still shown with one marker
+    (line, start, end.max(start + 1))
+}
+
+fn display_width(text: &str) -> usize {
+    text.chars().map(|c| if c == '\t' { TAB_WIDTH } else { 1 }).sum()
+}
+
+fn expand_tabs(text: &str) -> String {
+    text.replace('\t', &" ".repeat(TAB_WIDTH))
+}
@@ -0,0 +1,25 @@
+extern crate lang_parser;
+
+use lang_parser::Span;
+use errors::diagnostic::Diagnostic;
+use errors::codes::AMBIGUOUS_NAME;
+
+pub fn import_error(code: &str, msg: &str, namespace: &str, path: &str, span: Span) -> Diagnostic {
+    Diagnostic::error(code, format!("Import {} in {}: {}", path, namespace, msg))
+            .with_primary(span, "")
+}
+
+pub fn import_warning(code: &str, msg: &str, namespace: &str, path: &str, span: Span) -> Diagnostic {
+    Diagnostic::warning(code, format!("Import {} in {}: {}", path, namespace, msg))
+            .with_primary(span, "")
+}
+
+/**
+ * A name used in `namespace` is exported by several of its namespace imports
+ */
+pub fn ambiguous_name_error(name: &str, namespaces: &[String], namespace: &str, span: Span) -> Diagnostic {
+    Diagnostic::error(AMBIGUOUS_NAME, format!("Namespace {}: `{}` is ambiguous, it is imported from both {}",
+            namespace, name, namespaces.join(" and ")))
+            .with_primary(span, "")
+            .with_help(&format!("import it explicitly: `use {}.{};`", namespaces[0], name))
+}
@@ -0,0 +1,75 @@
+extern crate lang_parser;
+
+use lang_parser::Span;
+use errors::diagnostic::{Diagnostic, Label};
+use source_map::SourceMap;
+
+/**
+ * Renders a diagnostic as a JSON object written on a single line
+ */
+pub fn render(diagnostic: &Diagnostic, sources: &SourceMap) -> String {
+    let mut fields: Vec<String> = vec![
+        format!("\"code\":{}", string(diagnostic.get_code())),
+        format!("\"severity\":{}", string(diagnostic.get_severity().get_name())),
+        format!("\"message\":{}", string(diagnostic.get_message())),
+    ];
+    match diagnostic.get_primary() {
+        Some(primary) => {
+            let span = primary.get_span();
+            fields.push(format!("\"file\":{}", string(sources.get_path(span.get_file_id()))));
+            fields.push(format!("\"span\":{}", span_object(span, sources)));
+        },
+        None => {
+            fields.push(String::from("\"file\":null"));
+            fields.push(String::from("\"span\":null"));
+        }
+    }
+    let labels: Vec<String> = diagnostic.get_secondary().iter()
+            .map(|label| label_object(label, sources))
+            .collect();
+    fields.push(format!("\"labels\":[{}]", labels.join(",")));
+    match diagnostic.get_help() {
+        Some(help) => fields.push(format!("\"help\":{}", string(help))),
+        None => fields.push(String::from("\"help\":null")),
+    }
+
+    format!("{{{}}}", fields.join(","))
+}
+
+/**
+ * Quoted JSON string
+ */
+pub fn string(text: &str) -> String {
+    let mut escaped = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+/**
+ * Byte offsets of the span along with its start and end positions, lines
+ * and columns starting at 1 and the end being exclusive
+ */
+fn span_object(span: Span, sources: &SourceMap) -> String {
+    let lines = sources.get_lines(span.get_file_id());
+    let (start_line, start_column) = lines.line_col(span.get_start_byte());
+    let (end_line, end_column) = lines.line_col(span.get_end_byte());
+    format!("{{\"start_byte\":{},\"end_byte\":{},\"start\":{{\"line\":{},\"column\":{}}},\"end\":{{\"line\":{},\"column\":{}}}}}",
+            span.get_start_byte(), span.get_end_byte(), start_line, start_column, end_line, end_column)
+}
+
+fn label_object(label: &Label, sources: &SourceMap) -> String {
+    let span = label.get_span();
+    format!("{{\"file\":{},\"span\":{},\"message\":{}}}", string(sources.get_path(span.get_file_id())),
+            span_object(span, sources), string(label.get_message()))
+}
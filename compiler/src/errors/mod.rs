@@ -1,3 +1,10 @@
 pub mod classes;
+pub mod codes;
+pub mod diagnostic;
 pub mod files;
 pub mod functions;
+pub mod human;
+pub mod imports;
+pub mod json;
+pub mod sarif;
+pub mod suggestions;
@@ -0,0 +1,60 @@
+extern crate lang_parser;
+
+use lang_parser::Span;
+use errors::diagnostic::Diagnostic;
+use errors::json::string;
+use source_map::SourceMap;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/**
+ * Renders all the diagnostics of a compilation as a single SARIF 2.1.0 log
+ * with one run of the compiler
+ */
+pub fn render(diagnostics: &[Diagnostic], sources: &SourceMap) -> String {
+    let mut rule_ids: Vec<&str> = diagnostics.iter().map(|d| d.get_code()).collect();
+    rule_ids.sort();
+    rule_ids.dedup();
+    let rules: Vec<String> = rule_ids.iter()
+            .map(|id| format!("{{\"id\":{}}}", string(id)))
+            .collect();
+    let results: Vec<String> = diagnostics.iter()
+            .map(|diagnostic| result_object(diagnostic, sources))
+            .collect();
+
+    format!("{{\"$schema\":{},\"version\":\"2.1.0\",\"runs\":[{{\"tool\":{{\"driver\":{{\"name\":\"lang-compiler\",\"version\":{},\"rules\":[{}]}}}},\"results\":[{}]}}]}}",
+            string(SARIF_SCHEMA), string(env!("CARGO_PKG_VERSION")), rules.join(","), results.join(","))
+}
+
+fn result_object(diagnostic: &Diagnostic, sources: &SourceMap) -> String {
+    let mut fields: Vec<String> = vec![
+        format!("\"ruleId\":{}", string(diagnostic.get_code())),
+        format!("\"level\":{}", string(diagnostic.get_severity().get_name())),
+        format!("\"message\":{{\"text\":{}}}", string(diagnostic.get_message())),
+    ];
+    if let Some(primary) = diagnostic.get_primary() {
+        fields.push(format!("\"locations\":[{{\"physicalLocation\":{}}}]",
+                physical_location(primary.get_span(), sources)));
+    }
+    let related: Vec<String> = diagnostic.get_secondary().iter().enumerate()
+            .map(|(id, label)| format!("{{\"id\":{},\"physicalLocation\":{},\"message\":{{\"text\":{}}}}}",
+                    id, physical_location(label.get_span(), sources), string(label.get_message())))
+            .collect();
+    if !related.is_empty() {
+        fields.push(format!("\"relatedLocations\":[{}]", related.join(",")));
+    }
+    if let Some(help) = diagnostic.get_help() {
+        fields.push(format!("\"properties\":{{\"notes\":[{}]}}", string(&format!("help: {}", help))));
+    }
+
+    format!("{{{}}}", fields.join(","))
+}
+
+fn physical_location(span: Span, sources: &SourceMap) -> String {
+    let lines = sources.get_lines(span.get_file_id());
+    let (start_line, start_column) = lines.line_col(span.get_start_byte());
+    let (end_line, end_column) = lines.line_col(span.get_end_byte());
+    format!("{{\"artifactLocation\":{{\"uri\":{}}},\"region\":{{\"startLine\":{},\"startColumn\":{},\"endLine\":{},\"endColumn\":{},\"byteOffset\":{},\"byteLength\":{}}}}}",
+            string(sources.get_path(span.get_file_id())), start_line, start_column, end_line, end_column,
+            span.get_start_byte(), span.get_end_byte() - span.get_start_byte())
+}
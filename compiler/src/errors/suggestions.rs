@@ -0,0 +1,47 @@
+use std::cmp::{max, min};
+
+/**
+ * The candidate closest to `name` when it is close enough to be what was
+ * meant, ties are broken alphabetically
+ */
+pub fn did_you_mean<'a>(name: &str, candidates: &[&'a str]) -> Option<&'a str> {
+    let max_distance = max(1, name.chars().count() / 3);
+    let mut candidates = candidates.to_vec();
+    candidates.sort();
+    candidates.dedup();
+    candidates.into_iter()
+            .filter(|candidate| *candidate != name)
+            .map(|candidate| (edit_distance(name, candidate), candidate))
+            .filter(|(distance, _)| *distance <= max_distance)
+            .min_by_key(|(distance, _)| *distance)
+            .map(|(_, candidate)| candidate)
+}
+
+/**
+ * Number of insertions, deletions, substitutions and swaps of two adjacent
+ * characters needed to turn `a` into `b`
+ */
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    // distances[i][j] is the distance between the first i characters of `a`
+    // and the first j characters of `b`
+    let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, distance) in distances[0].iter_mut().enumerate() {
+        *distance = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            let mut distance = min(distances[i - 1][j - 1] + cost, min(distances[i - 1][j], distances[i][j - 1]) + 1);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = min(distance, distances[i - 2][j - 2] + 1);
+            }
+            distances[i][j] = distance;
+        }
+    }
+    distances[a.len()][b.len()]
+}
@@ -0,0 +1,524 @@
+extern crate lang_parser;
+
+use lang_parser::ast::*;
+use lang_parser::{Span, FileId};
+use source_file::SourceFile;
+use ast_actions::ast_visitor::AstVisitor;
+use symbol_table::GlobalSymbolTable;
+use errors::diagnostic::DiagnosticSink;
+use errors::codes::{UNKNOWN_IMPORT, AMBIGUOUS_NAME, IMPORT_CYCLE, INVALID_IMPORT_ALIAS, UNUSED_IMPORT};
+use errors::imports::import_err::{import_error, import_warning, ambiguous_name_error};
+use errors::suggestions::did_you_mean;
+use std::collections::{HashMap, HashSet};
+
+/**
+ * Namespaces of the standard library and the classes they export, they are
+ * not part of the compiled sources
+ */
+const STANDARD_LIBRARY: &[(&str, &[&str])] = &[
+    ("Lang", &["Console", "World"]),
+    ("Lang.Collections", &["Iterable", "List", "Map", "Set"]),
+    ("Lang.Hello", &["World"]),
+    ("Lang.Io", &["File", "Reader", "Writer"]),
+    ("Lang.Util", &["Hello", "World"]),
+];
+
+/**
+ * Scopes of the compiled files, keyed by file
+ */
+pub type FileScopes = HashMap<FileId, FileScope>;
+
+/**
+ * Names usable without qualification in a file: the classes and functions
+ * of its namespace and the ones brought by its imports
+ */
+#[derive(Debug)]
+pub struct FileScope {
+    namespace: String,
+    // classes and functions of the namespace of the file
+    declared: HashSet<String>,
+    imports: Vec<ResolvedImport>,
+    // name in the file -> indexes of the imports bringing it
+    imported: HashMap<String, Vec<usize>>,
+    // ambiguous names already reported in the file
+    reported_ambiguous: HashSet<String>,
+}
+
+/**
+ * An imported namespace or name, the names of a selection are imported
+ * separately
+ */
+#[derive(Debug)]
+struct ResolvedImport {
+    span: Span,
+    path: String,
+    target: ImportTarget,
+    used: bool,
+}
+
+#[derive(Debug)]
+enum ImportTarget {
+    // every class and function of the namespace
+    Namespace(String),
+    // (namespace, name)
+    Single(String, String),
+    // namespace of the standard library, what it exports is not known
+    External,
+}
+
+/**
+ * What an unqualified name refers to in a file
+ */
+pub enum ScopeLookup {
+    // (namespace, name in the namespace), they differ from the looked up
+    // name for aliased imports
+    Found(String, String),
+    // (namespaces exporting the name, brought by namespace imports)
+    Ambiguous(Vec<String>),
+    NotFound,
+}
+
+impl FileScope {
+    pub fn get_namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    /**
+     * Declaration of an unqualified class or function name. The names of
+     * the namespace of the file come first, then the single imports, then
+     * the namespace imports. The imports bringing the name are marked as
+     * used.
+     */
+    pub fn lookup(&mut self, name: &str) -> ScopeLookup {
+        for index in self.bringing_imports(name) {
+            self.imports[index].used = true;
+        }
+        self.resolve(name)
+    }
+
+    /**
+     * Declaration of an unqualified class or function name, like `lookup`
+     * but without marking the imports as used
+     */
+    pub fn resolve(&self, name: &str) -> ScopeLookup {
+        if self.declared.contains(name) {
+            return ScopeLookup::Found(self.namespace.clone(), String::from(name));
+        }
+        let mut found: Vec<(String, String)> = vec![];
+        for index in self.bringing_imports(name) {
+            match &self.imports[index].target {
+                ImportTarget::Namespace(namespace) => found.push((namespace.clone(), String::from(name))),
+                ImportTarget::Single(namespace, declared) => found.push((namespace.clone(), declared.clone())),
+                ImportTarget::External => {}
+            }
+        }
+        found.sort();
+        found.dedup();
+        match found.len() {
+            0 => ScopeLookup::NotFound,
+            1 => {
+                let (namespace, declared) = found.remove(0);
+                ScopeLookup::Found(namespace, declared)
+            },
+            _ => ScopeLookup::Ambiguous(found.into_iter().map(|(namespace, _)| namespace).collect()),
+        }
+    }
+
+    /**
+     * Imports an unqualified name refers to when it is not declared in the
+     * namespace of the file: a single import hides the namespace imports
+     */
+    fn bringing_imports(&self, name: &str) -> Vec<usize> {
+        if self.declared.contains(name) {
+            return vec![];
+        }
+        let indexes = match self.imported.get(name) {
+            Some(indexes) => indexes,
+            None => return vec![]
+        };
+        let single = indexes.iter().cloned().find(|index| matches!(self.imports[*index].target, ImportTarget::Single(..)));
+        match single {
+            Some(index) => vec![index],
+            None => indexes.clone(),
+        }
+    }
+
+    /**
+     * Whether the ambiguity of `name` is still to be reported, it is
+     * reported at its first use only
+     */
+    pub fn first_ambiguous_use(&mut self, name: &str) -> bool {
+        self.reported_ambiguous.insert(String::from(name))
+    }
+
+    /**
+     * Names brought by the imports of the file
+     */
+    pub fn imported_names(&self) -> Vec<&str> {
+        self.imported.keys().map(|name| name.as_str()).collect()
+    }
+
+    fn new(namespace: &str, gst: &GlobalSymbolTable) -> Self {
+        FileScope {
+            namespace: String::from(namespace),
+            declared: gst.get(namespace).map(|nst| nst.keys().cloned().collect()).unwrap_or_default(),
+            imports: vec![],
+            imported: HashMap::new(),
+            reported_ambiguous: HashSet::new(),
+        }
+    }
+
+    /**
+     * Adds an import, `alias` being the name a single import is known by in
+     * the file when it differs from the imported one
+     */
+    fn add_import(&mut self, span: Span, path: &str, target: ImportTarget, alias: Option<&str>, gst: &GlobalSymbolTable) {
+        let index = self.imports.len();
+        let names: Vec<String> = match &target {
+            ImportTarget::Namespace(namespace) => gst[namespace].keys().cloned().collect(),
+            ImportTarget::Single(_, name) => vec![String::from(alias.unwrap_or(name))],
+            ImportTarget::External => vec![],
+        };
+        for name in names {
+            self.imported.entry(name).or_default().push(index);
+        }
+        self.imports.push(ResolvedImport {
+            span,
+            path: String::from(path),
+            target,
+            used: false,
+        });
+    }
+
+    /**
+     * The single import already bringing another declaration under the
+     * name `local_name`
+     */
+    fn conflicting_import(&self, local_name: &str, namespace: &str, name: &str) -> Option<&ResolvedImport> {
+        self.imported.get(local_name)?.iter().map(|index| &self.imports[*index]).find(|import| match &import.target {
+            ImportTarget::Single(other_namespace, other_name) => other_namespace != namespace || other_name != name,
+            _ => false
+        })
+    }
+}
+
+/**
+ * Resolves the imports of every file against the global symbol table and
+ * gives the scope of each file. Unknown imports, aliased namespaces, names
+ * imported twice from different places, namespaces importing each other
+ * and type names exported by several imported namespaces are reported.
+ */
+pub fn resolve_imports(namespaces: &mut HashMap<String, Vec<SourceFile>>, gst: &GlobalSymbolTable,
+        sink: &mut DiagnosticSink) -> FileScopes {
+    let mut scopes = FileScopes::new();
+    let mut resolver = ImportResolver::new(gst, sink);
+    for source_files in namespaces.values_mut() {
+        for source_file in source_files {
+            let file_id = source_file.get_file_id();
+            let file = source_file.get_ast();
+            let mut scope = FileScope::new(file.get_namespace(), gst);
+            for import in file.get_imports().iter() {
+                resolver.resolve_import(&mut scope, import);
+            }
+            TypeNamesScanner::new(&mut scope, resolver.sink).visit_file(file);
+            scopes.insert(file_id, scope);
+        }
+    }
+    report_import_cycles(&resolver.edges, resolver.sink);
+
+    scopes
+}
+
+/**
+ * Warns about the imports no name of their file refers to, to be called
+ * once every pass looking names up is done
+ */
+pub fn report_unused_imports(scopes: &FileScopes, sink: &mut DiagnosticSink) {
+    let mut file_ids: Vec<&FileId> = scopes.keys().collect();
+    file_ids.sort();
+    for file_id in file_ids {
+        let scope = &scopes[file_id];
+        for import in &scope.imports {
+            // what a standard namespace exports is not known
+            let is_checked = matches!(import.target, ImportTarget::Namespace(_) | ImportTarget::Single(..));
+            if is_checked && !import.used {
+                sink.push(import_warning(UNUSED_IMPORT, "unused import", &scope.namespace, &import.path, import.span));
+            }
+        }
+    }
+}
+
+struct ImportResolver<'a> {
+    gst: &'a GlobalSymbolTable,
+    sink: &'a mut DiagnosticSink,
+    // namespace -> imports of other compiled namespaces it makes
+    edges: HashMap<String, Vec<ImportEdge>>,
+}
+
+impl<'a> ImportResolver<'a> {
+    pub fn new(gst: &'a GlobalSymbolTable, sink: &'a mut DiagnosticSink) -> Self {
+        ImportResolver {
+            gst,
+            sink,
+            edges: HashMap::new(),
+        }
+    }
+
+    fn resolve_import(&mut self, scope: &mut FileScope, import: &Import) {
+        let path = import.get_path();
+        let span = import.get_span();
+        match import.get_kind() {
+            ImportKind::Path(alias) => match (self.path_target(path), alias) {
+                (Some(ImportTarget::Namespace(_)), Some(_)) | (Some(ImportTarget::External), Some(_)) => {
+                    self.sink.push(import_error(INVALID_IMPORT_ALIAS, "only classes can be aliased",
+                            scope.get_namespace(), path, span)
+                            .with_help(&format!("import the namespace without alias: `use {};`", path)));
+                },
+                (Some(target), alias) => self.add_import(scope, span, path, target, alias.as_deref()),
+                (None, _) => {
+                    let candidates = importable_paths(self.gst, true, true);
+                    self.unknown_import(scope, "no namespace or class has this name", path, span, &candidates);
+                }
+            },
+            ImportKind::Selection(names) => {
+                for name in names {
+                    let path = format!("{}.{}", path, name.get_name());
+                    match self.single_target(&path) {
+                        Some(target) => self.add_import(scope, name.get_span(), &path, target, name.get_alias()),
+                        None => {
+                            let candidates = importable_paths(self.gst, false, true);
+                            self.unknown_import(scope, "no class has this name", &path, name.get_span(),
+                                    &candidates);
+                        }
+                    }
+                }
+            },
+            ImportKind::Wildcard => match self.namespace_target(path) {
+                Some(target) => self.add_import(scope, span, path, target, None),
+                None => {
+                    let candidates = importable_paths(self.gst, true, false);
+                    self.unknown_import(scope, "no namespace has this name", path, span, &candidates);
+                }
+            }
+        }
+    }
+
+    fn add_import(&mut self, scope: &mut FileScope, span: Span, path: &str, target: ImportTarget, alias: Option<&str>) {
+        if let ImportTarget::Single(namespace, name) = &target {
+            if let Some(other) = scope.conflicting_import(alias.unwrap_or(name), namespace, name) {
+                let diagnostic = import_error(AMBIGUOUS_NAME, &format!("`{}` is already imported", alias.unwrap_or(name)),
+                        scope.get_namespace(), path, span)
+                        .with_secondary(other.span, &format!("`{}` imported here", other.path));
+                self.sink.push(diagnostic);
+                return;
+            }
+        }
+        if let ImportTarget::Namespace(imported) | ImportTarget::Single(imported, _) = &target {
+            if imported != scope.get_namespace() && self.gst.contains_key(imported) {
+                self.edges.entry(String::from(scope.get_namespace())).or_default().push(ImportEdge {
+                    target: imported.clone(),
+                    span,
+                    path: String::from(path),
+                });
+            }
+        }
+        scope.add_import(span, path, target, alias, self.gst);
+    }
+
+    fn unknown_import(&mut self, scope: &FileScope, msg: &str, path: &str, span: Span, candidates: &[String]) {
+        let mut diagnostic = import_error(UNKNOWN_IMPORT, msg, scope.get_namespace(), path, span);
+        let candidates: Vec<&str> = candidates.iter().map(|candidate| candidate.as_str()).collect();
+        if let Some(suggestion) = did_you_mean(path, &candidates) {
+            diagnostic = diagnostic.with_help(&format!("did you mean `{}`?", suggestion));
+        }
+        self.sink.push(diagnostic);
+    }
+
+    /**
+     * `use A.B;`: a compiled namespace, then a class or function, then a
+     * namespace of the standard library
+     */
+    fn path_target(&self, path: &str) -> Option<ImportTarget> {
+        if self.gst.contains_key(path) {
+            return Some(ImportTarget::Namespace(String::from(path)));
+        }
+        self.single_target(path).or_else(|| self.namespace_target(path))
+    }
+
+    /**
+     * A class or function of a compiled namespace or of the standard library
+     */
+    fn single_target(&self, path: &str) -> Option<ImportTarget> {
+        let (namespace, name) = path.rsplit_once('.')?;
+        if self.gst.get(namespace).is_some_and(|nst| nst.contains_key(name)) || is_standard_name(namespace, name) {
+            Some(ImportTarget::Single(String::from(namespace), String::from(name)))
+        } else {
+            None
+        }
+    }
+
+    fn namespace_target(&self, path: &str) -> Option<ImportTarget> {
+        if self.gst.contains_key(path) {
+            Some(ImportTarget::Namespace(String::from(path)))
+        } else if is_standard_namespace(path) {
+            Some(ImportTarget::External)
+        } else {
+            None
+        }
+    }
+}
+
+fn is_standard_namespace(path: &str) -> bool {
+    STANDARD_LIBRARY.iter().any(|(namespace, _)| *namespace == path)
+}
+
+fn is_standard_name(namespace: &str, name: &str) -> bool {
+    STANDARD_LIBRARY.iter().any(|(standard, names)| *standard == namespace && names.contains(&name))
+}
+
+/**
+ * The namespaces and the classes and functions that can be imported, from
+ * the compiled sources and from the standard library
+ */
+fn importable_paths(gst: &GlobalSymbolTable, namespaces: bool, names: bool) -> Vec<String> {
+    let mut paths: Vec<String> = vec![];
+    for (namespace, nst) in gst {
+        if namespaces {
+            paths.push(namespace.clone());
+        }
+        if names {
+            paths.extend(nst.keys().map(|name| format!("{}.{}", namespace, name)));
+        }
+    }
+    for (namespace, exported) in STANDARD_LIBRARY {
+        if namespaces {
+            paths.push(String::from(*namespace));
+        }
+        if names {
+            paths.extend(exported.iter().map(|name| format!("{}.{}", namespace, name)));
+        }
+    }
+    paths
+}
+
+
+/**
+ * Import of a compiled namespace, or of something it declares, by another
+ * namespace
+ */
+struct ImportEdge {
+    target: String,
+    span: Span,
+    path: String,
+}
+
+#[derive(PartialEq)]
+enum VisitState {
+    InProgress,
+    Done,
+}
+
+/**
+ * Reports each cycle of namespaces importing each other once, at the
+ * import closing it
+ */
+fn report_import_cycles(edges: &HashMap<String, Vec<ImportEdge>>, sink: &mut DiagnosticSink) {
+    let mut namespaces: Vec<&String> = edges.keys().collect();
+    namespaces.sort();
+    let mut states: HashMap<String, VisitState> = HashMap::new();
+    for namespace in namespaces {
+        if !states.contains_key(namespace) {
+            visit_imports(namespace, edges, &mut states, &mut vec![], sink);
+        }
+    }
+}
+
+fn visit_imports(namespace: &str, edges: &HashMap<String, Vec<ImportEdge>>, states: &mut HashMap<String, VisitState>,
+        path: &mut Vec<String>, sink: &mut DiagnosticSink) {
+    states.insert(String::from(namespace), VisitState::InProgress);
+    path.push(String::from(namespace));
+    for edge in edges.get(namespace).into_iter().flatten() {
+        match states.get(&edge.target) {
+            Some(VisitState::InProgress) => {
+                let start = path.iter().position(|n| *n == edge.target).unwrap_or(0);
+                let mut cycle = path[start..].to_vec();
+                cycle.push(edge.target.clone());
+                sink.push(import_error(IMPORT_CYCLE, &format!("namespaces import each other: {}", cycle.join(" -> ")),
+                        namespace, &edge.path, edge.span));
+            },
+            Some(VisitState::Done) => {},
+            None => visit_imports(&edge.target, edges, states, path, sink),
+        }
+    }
+    path.pop();
+    states.insert(String::from(namespace), VisitState::Done);
+}
+
+/**
+ * Looks the type names of a file up so that the imports they use are
+ * known and the ambiguous ones are reported
+ */
+struct TypeNamesScanner<'a> {
+    scope: &'a mut FileScope,
+    sink: &'a mut DiagnosticSink,
+    // type params in scope, they shadow the imported names
+    type_params: Vec<String>,
+}
+
+impl<'a> TypeNamesScanner<'a> {
+    pub fn new(scope: &'a mut FileScope, sink: &'a mut DiagnosticSink) -> Self {
+        TypeNamesScanner {
+            scope,
+            sink,
+            type_params: vec![],
+        }
+    }
+}
+
+impl<'a> AstVisitor for TypeNamesScanner<'a> {
+    fn visit_class(&mut self, n: &mut Class) {
+        self.type_params = n.get_type_params().iter().map(|param| String::from(param.get_name())).collect();
+        for type_param in n.get_type_params() {
+            self.visit_type_param(type_param);
+        }
+        self.visit_type_ref(n.get_super_class());
+        for interface in n.get_implemented_interfaces() {
+            self.visit_type_ref(interface);
+        }
+        for member in n.get_members() {
+            self.visit_class_member(member);
+        }
+        self.type_params.clear();
+    }
+
+    fn visit_function(&mut self, n: &mut Function) {
+        let class_type_params = self.type_params.len();
+        self.type_params.extend(n.get_type_params().iter().map(|param| String::from(param.get_name())));
+        for type_param in n.get_type_params() {
+            self.visit_type_param(type_param);
+        }
+        for param in n.get_params() {
+            self.visit_param(param);
+        }
+        self.visit_type_ref(n.get_return_type());
+        for stmt in n.get_statements() {
+            self.visit_statement(stmt);
+        }
+        self.type_params.truncate(class_type_params);
+    }
+
+    fn visit_named_type(&mut self, n: &mut NamedType) {
+        let name = String::from(n.get_name());
+        if !self.type_params.contains(&name) {
+            if let ScopeLookup::Ambiguous(namespaces) = self.scope.lookup(&name) {
+                if self.scope.first_ambiguous_use(&name) {
+                    let diagnostic = ambiguous_name_error(&name, &namespaces, self.scope.get_namespace(), n.get_span());
+                    self.sink.push(diagnostic);
+                }
+            }
+        }
+        for type_arg in n.get_type_args() {
+            self.visit_type_ref(type_arg);
+        }
+    }
+}
@@ -1,50 +1,171 @@
 extern crate lang_parser;
 mod source_file;
+mod source_map;
 mod errors;
 mod ast_actions;
 mod symbol_table;
+mod closure_captures;
+mod import_resolution;
+mod class_hierarchy;
+mod name_resolution;
+mod control_flow_graph;
+mod attributes;
 
 use source_file::SourceFile;
 use ast_actions::{
     modifiers::{block_expander},
     validators::{
+        attributes_checker,
         class_methods_checker1,
-        functions_checker1
+        functions_checker1,
+        closure_types_checker,
+        index_checker,
+        type_checker,
+        implementation_checker,
+        flow_checker
     },
 };
+use source_map::SourceMap;
 use symbol_table::{create_symbol_table};
+use closure_captures::collect_captures;
+use import_resolution::{resolve_imports, report_unused_imports};
+use class_hierarchy::check_class_hierarchy;
+use name_resolution::{resolve_names, ResolutionTable};
+use control_flow_graph::build_graphs;
+use errors::diagnostic::{DiagnosticSink, ErrorFormat};
 use std::env;
+use std::process::exit;
 use std::collections::{HashMap, hash_map::Entry};
 
 fn main() {
-    let raw_asts = collect_raw_asts();
+    let options = parse_args();
+    let mut sources = SourceMap::new();
+    let mut sink = DiagnosticSink::new(options.error_format, &options.sarif_output);
+    let raw_asts = collect_raw_asts(&options.file_paths, &mut sources, &mut sink);
+    end_phase(&mut sink, &sources);
     let mut namespaces = namespaces_map(raw_asts);
 
     // debug
     for source_files in namespaces.values_mut() {
         for source_file in source_files {
             // validations
-            class_methods_checker1::check_methods_body(source_file);
-            functions_checker1::check_functions_body(source_file);
+            attributes_checker::check_attributes(source_file, &mut sink);
+            class_methods_checker1::check_methods_body(source_file, &mut sink);
+            functions_checker1::check_functions_body(source_file, &mut sink);
 
             // alterations
             block_expander::expand_blocks(source_file);
         }
     }
+    end_phase(&mut sink, &sources);
     /*for source_files in namespaces.values_mut() {
         for source_file in source_files {
             println!("{:#?}", source_file);
         }
     }*/
-    let symbols = create_symbol_table(namespaces);
-    println!("{:#?}", symbols);
+    let mut symbols = create_symbol_table(&mut namespaces, &mut sink);
+    collect_captures(&mut namespaces, &mut symbols, &mut sink);
+    let mut scopes = resolve_imports(&mut namespaces, &symbols, &mut sink);
+    let hierarchy = check_class_hierarchy(&mut namespaces, &symbols, &mut scopes, &mut sink);
+    let mut resolutions = ResolutionTable::new();
+    for source_files in namespaces.values_mut() {
+        for source_file in source_files {
+            resolve_names(source_file, &symbols, &mut scopes, &mut resolutions, &mut sink);
+            closure_types_checker::check_closure_types(source_file, &symbols, &scopes, &mut sink);
+            index_checker::check_indexes(source_file, &symbols, &scopes, &resolutions, &mut sink);
+            type_checker::check_types(source_file, &symbols, &scopes, &hierarchy, &mut sink);
+            implementation_checker::check_implementations(source_file, &symbols, &hierarchy, &mut sink);
+            flow_checker::check_flow(source_file, &symbols, &mut sink);
+        }
+    }
+    report_unused_imports(&scopes, &mut sink);
+    end_phase(&mut sink, &sources);
+    sink.finish(&sources);
+    if options.dump_cfg {
+        for source_files in namespaces.values_mut() {
+            for source_file in source_files {
+                for graph in build_graphs(source_file, &symbols) {
+                    println!("{}", graph.to_dot(source_file.get_code()));
+                }
+            }
+        }
+    } else {
+        println!("{:#?}", symbols);
+    }
+}
+
+struct Options {
+    error_format: ErrorFormat,
+    // file the SARIF log is written to
+    sarif_output: String,
+    // print the control flow graph of each function and closure in the
+    // Graphviz format
+    dump_cfg: bool,
+    file_paths: Vec<String>,
 }
 
-fn collect_raw_asts() -> Vec<SourceFile> {
-    env::args()
-            .skip(1)
-            .map(|file_path| SourceFile::from_file(&file_path))
-            .collect()
+/**
+ * Splits the command line into the `--error-format=human|json|sarif`,
+ * `--sarif-output=<path>` and `--dump-cfg` options and the source files paths
+ */
+fn parse_args() -> Options {
+    let mut error_format = ErrorFormat::Human;
+    let mut sarif_output = String::from("diagnostics.sarif");
+    let mut dump_cfg = false;
+    let mut file_paths: Vec<String> = vec![];
+    for arg in env::args().skip(1) {
+        if let Some(name) = arg.strip_prefix("--error-format=") {
+            error_format = match ErrorFormat::from_name(name) {
+                Some(format) => format,
+                None => {
+                    eprintln!("error: unknown error format `{}`, expected human, json or sarif", name);
+                    exit(1);
+                }
+            };
+        } else if let Some(path) = arg.strip_prefix("--sarif-output=") {
+            sarif_output = String::from(path);
+        } else if arg == "--dump-cfg" {
+            dump_cfg = true;
+        } else {
+            file_paths.push(arg);
+        }
+    }
+
+    Options {
+        error_format,
+        sarif_output,
+        dump_cfg,
+        file_paths,
+    }
+}
+
+/**
+ * Prints the diagnostics of the phase that just ended, the compilation
+ * stops there if some of them are errors
+ */
+fn end_phase(sink: &mut DiagnosticSink, sources: &SourceMap) {
+    if sink.flush(sources) {
+        sink.finish(sources);
+        exit(1);
+    }
+}
+
+fn collect_raw_asts(file_paths: &[String], sources: &mut SourceMap, sink: &mut DiagnosticSink) -> Vec<SourceFile> {
+    let mut source_files: Vec<SourceFile> = vec![];
+    for file_path in file_paths {
+        match SourceFile::from_file(file_path, sources) {
+            Ok(source_file) => {
+                source_files.push(source_file);
+            },
+            Err(diagnostics) => {
+                for diagnostic in diagnostics {
+                    sink.push(diagnostic);
+                }
+            }
+        }
+    }
+
+    source_files
 }
 
 fn namespaces_map(asts: Vec<SourceFile>) -> HashMap<String, Vec<SourceFile>> {
@@ -0,0 +1,597 @@
+extern crate lang_parser;
+
+use lang_parser::ast::*;
+use lang_parser::Span;
+use source_file::SourceFile;
+use ast_actions::ast_visitor::AstVisitor;
+use symbol_table::{GlobalSymbolTable, NSTEntry, CSTEntry, FunctionSymbolTable, member_name, get_overloads};
+use errors::diagnostic::{Diagnostic, DiagnosticSink};
+use errors::codes::{UNRESOLVED_NAME, UNKNOWN_MEMBER, USED_BEFORE_DECLARATION};
+use errors::classes::class_err::{class_member_error};
+use errors::functions::function_err::{function_error};
+use errors::imports::import_err::ambiguous_name_error;
+use errors::suggestions::did_you_mean;
+use import_resolution::{FileScopes, FileScope, ScopeLookup};
+use std::collections::HashMap;
+use std::fmt;
+
+/**
+ * Declarations the identifiers and called names refer to, keyed by the span
+ * of the `Identifier` or `FunctionCall` node
+ */
+pub type ResolutionTable = HashMap<Span, Resolution>;
+
+#[derive(Clone, Debug)]
+pub enum Resolution {
+    Local(Span), // (declaration of the param or variable)
+    This(String), // (class)
+    Super(String), // (superclass)
+    Field(String, Span), // (qualified name of the declaring class, declaration)
+    Method(String, Span), // (qualified name of the declaring class, declaration)
+    Overloads(Vec<(String, Span)>), // (declaring class and declaration of each candidate), picked by the type checker
+    Function(String, Span), // (qualified name, declaration)
+    Class(String, Option<Span>), // (qualified name, declaration), builtin classes have no declaration
+    Import(String), // (qualified name), imported from the standard library
+}
+
+impl Resolution {
+    /**
+     * Declarations the name may refer to, none for `this`, `super`, the
+     * builtin classes and the names of the standard library
+     */
+    pub fn get_declarations(&self) -> Vec<Span> {
+        match self {
+            Resolution::Local(declaration) | Resolution::Field(_, declaration) | Resolution::Method(_, declaration)
+                    | Resolution::Function(_, declaration) | Resolution::Class(_, Some(declaration)) => vec![*declaration],
+            Resolution::Overloads(candidates) => candidates.iter().map(|(_, declaration)| *declaration).collect(),
+            Resolution::This(_) | Resolution::Super(_) | Resolution::Class(_, None) | Resolution::Import(_) => vec![],
+        }
+    }
+}
+
+impl fmt::Display for Resolution {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Resolution::Local(_) => write!(f, "a param or variable"),
+            Resolution::This(class) => write!(f, "the current object of class {}", class),
+            Resolution::Super(class) => write!(f, "the current object as a {}", class),
+            Resolution::Field(class, _) => write!(f, "a field of {}", class),
+            Resolution::Method(class, _) => write!(f, "a method of {}", class),
+            Resolution::Overloads(_) => write!(f, "an overloaded method"),
+            Resolution::Function(function, _) => write!(f, "the function {}", function),
+            Resolution::Class(class, _) => write!(f, "the class {}", class),
+            Resolution::Import(name) => write!(f, "{} of the standard library", name),
+        }
+    }
+}
+
+/**
+ * Classes usable without being declared or imported
+ */
+pub const BUILTIN_CLASSES: &[&str] = &[
+    "Object", "Interface", "Closure", "String", "List", "Map", "Void", "Bool", "Char",
+    "Int8", "Int16", "Int", "Int64", "UInt8", "UInt16", "UInt", "UInt64", "Float", "Double",
+];
+
+/**
+ * Methods every object inherits from `Object`
+ */
+pub const OBJECT_METHODS: &[&str] = &["init", "toString", "hashcode", "equals"];
+
+/**
+ * Resolves the identifiers and called names of a file to their declarations
+ * and reports the ones that refer to nothing. Members are only resolved when
+ * the class of their receiver is known.
+ */
+pub fn resolve_names(s: &mut SourceFile, gst: &GlobalSymbolTable, scopes: &mut FileScopes,
+        resolutions: &mut ResolutionTable, sink: &mut DiagnosticSink) {
+    let scope = scopes.get_mut(&s.get_file_id()).expect("imports are resolved before names");
+    let mut resolver = NameResolver::new(gst, scope, resolutions, sink);
+    resolver.visit_file(s.get_ast());
+}
+
+/**
+ * Result of the lookup of a member in a class hierarchy
+ */
+enum MemberLookup<'a> {
+    // (qualified name of the declaring class, member)
+    Found(String, &'a CSTEntry),
+    // several overloads take that many arguments
+    // (qualified name of the declaring class and method, for each overload)
+    Overloaded(Vec<(String, &'a CSTEntry)>),
+    // the member names of the hierarchy
+    Missing(Vec<&'a str>),
+    // the hierarchy leaves the current namespace
+    Unknown,
+}
+
+struct NameResolver<'a> {
+    gst: &'a GlobalSymbolTable,
+    scope: &'a mut FileScope,
+    resolutions: &'a mut ResolutionTable,
+    sink: &'a mut DiagnosticSink,
+    namespace: String,
+    class: Option<String>,
+    function: String,
+    // symbol table of the function being visited
+    fst: Option<&'a FunctionSymbolTable>,
+    // params and variables with their declaration and type, innermost
+    // scope last
+    scopes: Vec<HashMap<String, (Span, TypeRef)>>,
+}
+
+impl<'a> NameResolver<'a> {
+    pub fn new(gst: &'a GlobalSymbolTable, scope: &'a mut FileScope, resolutions: &'a mut ResolutionTable,
+            sink: &'a mut DiagnosticSink) -> Self {
+        NameResolver {
+            gst,
+            scope,
+            resolutions,
+            sink,
+            namespace: String::from(""),
+            class: None,
+            function: String::from(""),
+            fst: None,
+            scopes: vec![],
+        }
+    }
+
+    fn error(&self, code: &str, msg: &str, span: Span) -> Diagnostic {
+        match &self.class {
+            Some(class) => class_member_error(code, msg, &self.namespace, class, &self.function, span),
+            None => function_error(code, msg, &self.namespace, &self.function, span),
+        }
+    }
+
+    fn qualified(&self, name: &str) -> String {
+        format!("{}.{}", self.namespace, name)
+    }
+
+    fn nst_entry(&self, name: &str) -> Option<&'a NSTEntry> {
+        self.gst.get(&self.namespace)?.get(name)
+    }
+
+    fn declare(&mut self, name: &str, span: Span, var_type: TypeRef) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(String::from(name), (span, var_type));
+        }
+    }
+
+    fn local(&self, name: &str) -> Option<&(Span, TypeRef)> {
+        self.scopes.iter().rev().filter_map(|scope| scope.get(name)).next()
+    }
+
+    /**
+     * Symbol table of the freestanding function or method of the current
+     * class declared at `declaration`
+     */
+    fn function_table(&self, name: &str, declaration: Span) -> Option<&'a FunctionSymbolTable> {
+        match (&self.class, self.nst_entry(self.class.as_deref().unwrap_or(name))?) {
+            (None, NSTEntry::Fun(_, _, _, span, fst)) if *span == declaration => Some(fst),
+            (Some(_), NSTEntry::Class(_, _, _, _, cst)) => cst.values().find_map(|entry| match entry {
+                CSTEntry::Method(_, _, _, span, fst) if *span == declaration => Some(fst),
+                _ => None
+            }),
+            _ => None
+        }
+    }
+
+    /**
+     * Reports a name exported by several imported namespaces, once per file
+     */
+    fn ambiguous_name(&mut self, name: &str, namespaces: &[String], span: Span) {
+        if self.scope.first_ambiguous_use(name) {
+            self.sink.push(ambiguous_name_error(name, namespaces, &self.namespace, span));
+        }
+    }
+
+    /**
+     * Reports a name used before the declaration of the param or variable
+     * it refers to, true when it is one. Closures capturing a variable
+     * declared after them are left to the capture analysis.
+     */
+    fn check_declared_later(&mut self, name: &str, span: Span) -> bool {
+        let fst = match self.fst {
+            Some(fst) => fst,
+            None => return false
+        };
+        let position = span.get_start_byte();
+        let scope = fst.scope_at(position);
+        let (declared_in, declaration) = match fst.find_declared_after(scope, name, position) {
+            Some(found) => found,
+            None => return false
+        };
+        if fst.closures_between(scope, declared_in).is_empty() {
+            let diagnostic = self.error(USED_BEFORE_DECLARATION, &format!("`{}` is used before its declaration", name), span)
+                    .with_secondary(declaration.get_span(), "declared here");
+            self.sink.push(diagnostic);
+        }
+        true
+    }
+
+    /**
+     * Declaration of a name imported from `namespace`, the standard library
+     * is not compiled so its names have none
+     */
+    fn imported(&self, namespace: &str, name: &str) -> Resolution {
+        let qualified = format!("{}.{}", namespace, name);
+        match self.gst.get(namespace).and_then(|nst| nst.get(name)) {
+            Some(NSTEntry::Class(_, _, _, declaration, _)) => Resolution::Class(qualified, Some(*declaration)),
+            Some(NSTEntry::Fun(_, _, _, declaration, _)) => Resolution::Function(qualified, *declaration),
+            None => Resolution::Import(qualified),
+        }
+    }
+
+    /**
+     * Looks up a member in a class, its superclasses and the interfaces they
+     * implement, the methods inherited from `Object` have no declaration.
+     * The overloads of a method are gathered from the whole hierarchy, an
+     * overriding method hiding the one it overrides, then narrowed down to
+     * the ones taking `args_count` arguments when some do.
+     */
+    fn lookup_member(&self, class: &str, name: &str, args_count: Option<usize>) -> MemberLookup<'a> {
+        let mut to_visit = vec![String::from(class)];
+        let mut visited: Vec<String> = vec![];
+        let mut members: Vec<&'a str> = vec![];
+        // (declaring class, mangled name, method)
+        let mut overloads: Vec<(String, &'a String, &'a CSTEntry)> = vec![];
+        while let Some(class) = to_visit.pop() {
+            if class == "Object" {
+                if OBJECT_METHODS.contains(&name) && overloads.is_empty() {
+                    return MemberLookup::Unknown;
+                }
+                members.extend(OBJECT_METHODS);
+            }
+            if class == "Object" || class == "Interface" || visited.contains(&class) {
+                continue;
+            }
+            let (super_class, interfaces, cst) = match self.nst_entry(&class) {
+                Some(NSTEntry::Class(_, super_class, interfaces, _, cst)) => (super_class, interfaces, cst),
+                _ if overloads.is_empty() => return MemberLookup::Unknown,
+                _ => continue
+            };
+            if let (Some(field), true) = (cst.get(name), overloads.is_empty()) {
+                return MemberLookup::Found(self.qualified(&class), field);
+            }
+            for (key, entry) in get_overloads(cst, name) {
+                if !overloads.iter().any(|(_, other, _)| *other == key) {
+                    overloads.push((self.qualified(&class), key, entry));
+                }
+            }
+            members.extend(cst.keys().map(|member| member_name(member)));
+            for parent in Some(super_class).into_iter().chain(interfaces.iter()) {
+                if let TypeRef::Named(t) = parent {
+                    to_visit.push(String::from(t.get_name()));
+                }
+            }
+            visited.push(class);
+        }
+        let takes_args = |entry: &CSTEntry| match (entry, args_count) {
+            (CSTEntry::Method(_, param_types, _, _, _), Some(count)) => param_types.len() == count,
+            _ => true
+        };
+        let mut candidates: Vec<(String, &'a CSTEntry)> = overloads.iter()
+                .filter(|(_, _, entry)| takes_args(entry))
+                .map(|(class, _, entry)| (class.clone(), *entry))
+                .collect();
+        if candidates.is_empty() {
+            candidates = overloads.into_iter().map(|(class, _, entry)| (class, entry)).collect();
+        }
+        match candidates.len() {
+            0 => MemberLookup::Missing(members),
+            1 => {
+                let (class, entry) = candidates.remove(0);
+                MemberLookup::Found(class, entry)
+            },
+            _ => MemberLookup::Overloaded(candidates)
+        }
+    }
+
+    /**
+     * Names an identifier at the start of a qualified expression can refer to
+     */
+    fn visible_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.scopes.iter().flat_map(|scope| scope.keys().map(|name| name.as_str())).collect();
+        if self.class.is_some() {
+            names.extend(&["this", "self", "super"]);
+        }
+        if let Some(nst) = self.gst.get(&self.namespace) {
+            names.extend(nst.keys().map(|name| name.as_str()));
+        }
+        names.extend(self.scope.imported_names());
+        names.extend(BUILTIN_CLASSES);
+        names
+    }
+
+    fn unresolved(&self, code: &str, msg: &str, name: &str, candidates: &[&str], span: Span) -> Diagnostic {
+        let diagnostic = self.error(code, msg, span);
+        match did_you_mean(name, candidates) {
+            Some(suggestion) => diagnostic.with_help(&format!("did you mean `{}`?", suggestion)),
+            None => diagnostic
+        }
+    }
+
+    /**
+     * Resolves an identifier starting a qualified expression, gives the
+     * class of the value it refers to when it is known
+     */
+    fn resolve_identifier(&mut self, name: &str, span: Span) -> Option<String> {
+        let (resolution, class) = match (self.local(name), &self.class) {
+            (Some((declaration, var_type)), _) => (Resolution::Local(*declaration), type_name(var_type)),
+            (None, Some(class)) if name == "this" || name == "self" => {
+                (Resolution::This(class.clone()), Some(class.clone()))
+            },
+            (None, Some(class)) if name == "super" => {
+                let super_class = match self.nst_entry(class) {
+                    Some(NSTEntry::Class(_, super_class, _, _, _)) => type_name(super_class),
+                    _ => None
+                }?;
+                (Resolution::Super(super_class.clone()), Some(super_class))
+            },
+            _ => match (self.nst_entry(name), self.scope.lookup(name)) {
+                (Some(NSTEntry::Class(_, _, _, declaration, _)), _) => {
+                    (Resolution::Class(self.qualified(name), Some(*declaration)), Some(String::from(name)))
+                },
+                (Some(NSTEntry::Fun(_, _, _, declaration, _)), _) => {
+                    (Resolution::Function(self.qualified(name), *declaration), None)
+                },
+                (None, ScopeLookup::Found(namespace, declared)) => (self.imported(&namespace, &declared), None),
+                (None, ScopeLookup::Ambiguous(namespaces)) => {
+                    self.ambiguous_name(name, &namespaces, span);
+                    return None;
+                },
+                (None, ScopeLookup::NotFound) if BUILTIN_CLASSES.contains(&name) => {
+                    (Resolution::Class(String::from(name), None), None)
+                },
+                (None, ScopeLookup::NotFound) => {
+                    if self.check_declared_later(name, span) {
+                        return None;
+                    }
+                    let mut diagnostic = self.error(UNRESOLVED_NAME, &format!("cannot find `{}` in this scope", name), span);
+                    let is_field = match &self.class {
+                        Some(class) => matches!(self.lookup_member(class, name, None), MemberLookup::Found(_, CSTEntry::Field(..))),
+                        None => false
+                    };
+                    if is_field {
+                        diagnostic = diagnostic.with_help(&format!("`{}` is a field, use `this.{}`", name, name));
+                    } else if let Some(suggestion) = did_you_mean(name, &self.visible_names()) {
+                        diagnostic = diagnostic.with_help(&format!("did you mean `{}`?", suggestion));
+                    }
+                    self.sink.push(diagnostic);
+                    return None;
+                }
+            }
+        };
+        self.resolutions.insert(span, resolution);
+        class
+    }
+
+    /**
+     * Resolves a call starting a qualified expression: closure variable,
+     * freestanding function, method of the current class or imported name
+     */
+    fn resolve_call(&mut self, call: &mut FunctionCall) -> Option<String> {
+        self.visit_function_call(call);
+        let args_count = call.get_param_exprs().len();
+        let name = call.get_name();
+        let span = call.get_span();
+        let method = match &self.class {
+            Some(class) => self.lookup_member(class, name, Some(args_count)),
+            None => MemberLookup::Missing(vec![]),
+        };
+        let (resolution, class) = match (self.local(name), self.nst_entry(name), method) {
+            (Some((declaration, _)), _, _) => (Resolution::Local(*declaration), None),
+            (None, Some(NSTEntry::Fun(_, _, return_type, declaration, _)), _) => {
+                (Resolution::Function(self.qualified(name), *declaration), type_name(return_type))
+            },
+            (None, _, MemberLookup::Found(class, CSTEntry::Method(_, _, return_type, declaration, _))) => {
+                (Resolution::Method(class, *declaration), type_name(return_type))
+            },
+            (None, _, MemberLookup::Overloaded(overloads)) => overloads_resolution(&overloads),
+            (None, _, method) => match self.scope.lookup(name) {
+                ScopeLookup::Found(namespace, declared) => (self.imported(&namespace, &declared), None),
+                ScopeLookup::Ambiguous(namespaces) => {
+                    self.ambiguous_name(name, &namespaces, span);
+                    return None;
+                },
+                ScopeLookup::NotFound => {
+                    if self.check_declared_later(name, span) {
+                        return None;
+                    }
+                    let mut candidates = self.visible_names();
+                    if let MemberLookup::Missing(members) = method {
+                        candidates.extend(members);
+                    }
+                    let diagnostic = self.unresolved(UNRESOLVED_NAME,
+                            &format!("cannot find function `{}` in this scope", name), name, &candidates, span);
+                    self.sink.push(diagnostic);
+                    return None;
+                }
+            }
+        };
+        self.resolutions.insert(span, resolution);
+        class
+    }
+
+    /**
+     * Resolves a field or method of an object of class `class`, gives the
+     * class of the value it refers to when it is known
+     */
+    fn resolve_member(&mut self, class: &str, name: &str, args_count: Option<usize>, span: Span) -> Option<String> {
+        match self.lookup_member(class, name, args_count) {
+            MemberLookup::Found(declaring_class, entry) => {
+                let (resolution, member_class) = match entry {
+                    CSTEntry::Field(field_type, declaration) => {
+                        (Resolution::Field(declaring_class, *declaration), type_name(field_type))
+                    },
+                    CSTEntry::Method(_, _, return_type, declaration, _) => {
+                        (Resolution::Method(declaring_class, *declaration), type_name(return_type))
+                    },
+                };
+                self.resolutions.insert(span, resolution);
+                member_class
+            },
+            MemberLookup::Overloaded(overloads) => {
+                let (resolution, member_class) = overloads_resolution(&overloads);
+                self.resolutions.insert(span, resolution);
+                member_class
+            },
+            MemberLookup::Missing(members) => {
+                let diagnostic = self.unresolved(UNKNOWN_MEMBER, &format!("{} has no member `{}`", class, name),
+                        name, &members, span);
+                self.sink.push(diagnostic);
+                None
+            },
+            MemberLookup::Unknown => None
+        }
+    }
+
+    fn visit_scoped_statements(&mut self, statements: &mut Vec<Statement>) {
+        self.scopes.push(HashMap::new());
+        for stmt in statements {
+            self.visit_statement(stmt);
+        }
+        self.scopes.pop();
+    }
+}
+
+/**
+ * Name of the class of the values of a type, closures have none
+ */
+fn type_name(t: &TypeRef) -> Option<String> {
+    match t {
+        TypeRef::Named(n) => Some(String::from(n.get_name())),
+        TypeRef::Closure(_) => None,
+    }
+}
+
+/**
+ * Resolution of a call to an overloaded method, with the class of the value
+ * it returns when all the overloads return the same
+ */
+fn overloads_resolution(overloads: &[(String, &CSTEntry)]) -> (Resolution, Option<String>) {
+    let mut declarations = vec![];
+    let mut return_classes = vec![];
+    for (class, entry) in overloads {
+        if let CSTEntry::Method(_, _, return_type, declaration, _) = entry {
+            declarations.push((class.clone(), *declaration));
+            return_classes.push(type_name(return_type));
+        }
+    }
+    return_classes.dedup();
+    let return_class = if return_classes.len() == 1 { return_classes.remove(0) } else { None };
+    (Resolution::Overloads(declarations), return_class)
+}
+
+impl<'a> AstVisitor for NameResolver<'a> {
+    fn visit_file(&mut self, n: &mut File) {
+        self.namespace = String::from(n.get_namespace());
+        for entity in n.get_entities() {
+            match entity {
+                FirstClassEntity::Function(f) => {
+                    self.class = None;
+                    self.visit_function(f);
+                },
+                FirstClassEntity::Class(c) => {
+                    self.class = Some(String::from(c.get_name()));
+                    self.visit_class(c);
+                },
+            }
+        }
+    }
+
+    fn visit_function(&mut self, n: &mut Function) {
+        self.function = String::from(n.get_name());
+        self.fst = self.function_table(n.get_name(), n.get_span());
+        self.scopes.push(HashMap::new());
+        for param in n.get_params() {
+            self.visit_param(param);
+        }
+        for stmt in n.get_statements() {
+            self.visit_statement(stmt);
+        }
+        self.scopes.pop();
+    }
+
+    fn visit_closure(&mut self, n: &mut Closure) {
+        self.scopes.push(HashMap::new());
+        for param in n.get_params() {
+            self.visit_param(param);
+        }
+        for stmt in n.get_statements() {
+            self.visit_statement(stmt);
+        }
+        self.scopes.pop();
+    }
+
+    fn visit_param(&mut self, n: &mut Param) {
+        let param_type = n.get_type().clone();
+        self.declare(n.get_name(), n.get_span(), param_type);
+    }
+
+    fn visit_variable_declaration(&mut self, n: &mut VariableDeclaration) {
+        if let Some(e) = n.get_value() {
+            self.visit_expression(e);
+        }
+        let var_type = n.get_type().clone();
+        self.declare(n.get_name(), n.get_span(), var_type);
+    }
+
+    fn visit_if_statement(&mut self, n: &mut IfStatement) {
+        self.visit_expression(n.get_condition());
+        self.visit_scoped_statements(n.get_statements());
+        if let Some(else_statements) = n.get_else_statements() {
+            self.visit_scoped_statements(else_statements);
+        }
+    }
+
+    fn visit_while_statement(&mut self, n: &mut WhileStatement) {
+        self.visit_expression(n.get_condition());
+        self.visit_scoped_statements(n.get_statements());
+    }
+
+    fn visit_for_statement(&mut self, n: &mut ForStatement) {
+        // the loop variable is only visible in the loop
+        self.scopes.push(HashMap::new());
+        if let Some(init) = n.get_init() {
+            self.visit_statement(init);
+        }
+        if let Some(condition) = n.get_condition() {
+            self.visit_expression(condition);
+        }
+        if let Some(update) = n.get_update() {
+            self.visit_statement(update);
+        }
+        self.visit_scoped_statements(n.get_statements());
+        self.scopes.pop();
+    }
+
+    fn visit_qualified_expression(&mut self, n: &mut QualifiedExpression) {
+        // class of the value the previous part refers to, when it is known
+        let mut receiver: Option<String> = None;
+        for (index, part) in n.get_parts().iter_mut().enumerate() {
+            receiver = match (index, part, receiver) {
+                (0, QualifiedExpressionPart::Identifier(i), _) => self.resolve_identifier(i.get_name(), i.get_span()),
+                (0, QualifiedExpressionPart::MethodCall(call), _) => self.resolve_call(call),
+                (_, QualifiedExpressionPart::Identifier(i), Some(class)) => {
+                    self.resolve_member(&class, i.get_name(), None, i.get_span())
+                },
+                // objects are created by the `.init` methods, which one is
+                // called is not known here
+                (_, QualifiedExpressionPart::MethodCall(call), Some(class)) if call.get_name() == "new" => {
+                    self.visit_function_call(call);
+                    Some(class)
+                },
+                (_, QualifiedExpressionPart::MethodCall(call), Some(class)) => {
+                    self.visit_function_call(call);
+                    let args_count = call.get_param_exprs().len();
+                    self.resolve_member(&class, call.get_name(), Some(args_count), call.get_span())
+                },
+                (_, part, _) => {
+                    self.visit_qualified_expression_part(part);
+                    None
+                }
+            };
+        }
+    }
+}
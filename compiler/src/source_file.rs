@@ -1,17 +1,25 @@
 extern crate lang_parser;
 
 use lang_parser::ast::File;
-use errors::files::{no_file_error};
+use lang_parser::{ParseDiagnostic, FileId};
+use errors::diagnostic::Diagnostic;
+use errors::files::{no_file_error, parse_error};
+use source_map::SourceMap;
 use std::fs;
 
 #[derive(Clone, Debug)]
 pub struct SourceFile {
+    file_id: FileId,
     path: String,
     code: String,
     ast: File,
 }
 
 impl SourceFile {
+    pub fn get_file_id(&self) -> FileId {
+        self.file_id
+    }
+
     pub fn get_path(&self) -> &str {
         &self.path
     }
@@ -24,32 +32,29 @@ impl SourceFile {
         &mut self.ast
     }
 
-    pub fn from_file(path: &String) -> Self {
-        let mut code_string: String = String::new();
-
-        match fs::read_to_string(path) {
-            Ok(code) => {
-                code_string.insert_str(0, &code);
-            },
-            Err(_) => {
-                no_file_error(path);
-            }
-        }
-
-        let ast = lang_parser::parse(&code_string.to_owned());
-
-        SourceFile {
-            path: path.to_owned(),
-            code: code_string,
-            ast,
-        }
+    /**
+     * Reads and parses a file, the file is added to `sources` even when it
+     * contains syntax errors so that they can be shown
+     */
+    pub fn from_file(path: &str, sources: &mut SourceMap) -> Result<Self, Vec<Diagnostic>> {
+        let code = match fs::read_to_string(path) {
+            Ok(code) => code,
+            Err(_) => return Err(vec![no_file_error(path)])
+        };
+        let file_id = sources.add_file(path, &code);
+
+        SourceFile::new(file_id, String::from(path), code)
+                .map_err(|diagnostics| diagnostics.iter().map(parse_error).collect())
     }
 
-    pub fn new(path: String, code: String) -> Self {
-        SourceFile {
+    pub fn new(file_id: FileId, path: String, code: String) -> Result<Self, Vec<ParseDiagnostic>> {
+        let ast = lang_parser::parse(file_id, &path, &code)?;
+
+        Ok(SourceFile {
+            file_id,
             path,
-            code: code.clone(),
-            ast: lang_parser::parse(&code.clone()),
-        }
+            code,
+            ast,
+        })
     }
 }
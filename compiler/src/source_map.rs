@@ -0,0 +1,33 @@
+extern crate lang_parser;
+
+use lang_parser::{FileId, LineIndex};
+
+/**
+ * Path and lines of every source file given to the compiler, used to show
+ * where a span is. A file id is the index of the file in the map.
+ */
+#[derive(Clone, Debug, Default)]
+pub struct SourceMap {
+    files: Vec<(String, LineIndex)>,
+}
+
+impl SourceMap {
+    pub fn new() -> Self {
+        SourceMap {
+            files: vec![],
+        }
+    }
+
+    pub fn add_file(&mut self, path: &str, code: &str) -> FileId {
+        self.files.push((String::from(path), LineIndex::new(code)));
+        self.files.len() - 1
+    }
+
+    pub fn get_path(&self, file_id: FileId) -> &str {
+        &self.files[file_id].0
+    }
+
+    pub fn get_lines(&self, file_id: FileId) -> &LineIndex {
+        &self.files[file_id].1
+    }
+}
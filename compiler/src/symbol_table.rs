@@ -1,8 +1,13 @@
 extern crate lang_parser;
 
 use lang_parser::ast::*;
+use lang_parser::Span;
 use source_file::SourceFile;
 use ast_actions::ast_visitor::AstVisitor;
+use errors::diagnostic::{Diagnostic, DiagnosticSink};
+use errors::codes::{DUPLICATE_DEFINITION, SHADOWED_NAME};
+use errors::classes::class_err::{class_error, class_member_error, class_member_warning};
+use errors::functions::function_err::{function_error, function_warning};
 use std::collections::HashMap;
 
 // @@image(../../.docs/compiler/symbol_table.jpg)
@@ -21,78 +26,546 @@ pub type NamespaceSymbolTable = HashMap<String, NSTEntry>;
 
 #[derive(Clone, Debug)]
 pub enum NSTEntry {
-    Class(String, Vec<String>, (usize, usize), ClassSymbolTable), // (base class, implemented interfaces, pos, CST)
-    Fun(String, (usize, usize), FunctionSymbolTable), // (return type, pos, FST)
+    Class(Vec<TypeParam>, TypeRef, Vec<TypeRef>, Span, ClassSymbolTable), // (type params, base class, implemented interfaces, span, CST)
+    Fun(Vec<TypeParam>, Vec<TypeRef>, TypeRef, Span, FunctionSymbolTable), // (type params, param types, return type, span, FST)
 }
 
 /**
  * Third level symbol table.
- * Contains the defined fields and methods in a class.
+ * Contains the defined fields and methods in a class. Fields are keyed by
+ * their name, methods by their mangled name so that overloads coexist.
  */
 pub type ClassSymbolTable = HashMap<String, CSTEntry>;
 
 #[derive(Clone, Debug)]
 pub enum CSTEntry {
-    Field(String, (usize, usize)), // (type, pos)
-    Method(String, (usize, usize), FunctionSymbolTable), // (return type, pos, FST)
+    Field(TypeRef, Span), // (type, span)
+    Method(Vec<TypeParam>, Vec<TypeRef>, TypeRef, Span, FunctionSymbolTable), // (type params, param types, return type, span, FST)
+}
+
+/**
+ * Internal name of a method: its name followed by its param types, the
+ * type params of the method being numbered by position so that `get<T>(a: T)`
+ * and `get<U>(a: U)` have the same signature: `get($0)`
+ */
+pub fn mangle(name: &str, type_params: &[TypeParam], param_types: &[TypeRef]) -> String {
+    let positions: HashMap<String, TypeRef> = type_params.iter().enumerate()
+            .map(|(i, p)| (String::from(p.get_name()), TypeRef::Named(NamedType::new(p.get_span(), format!("${}", i), vec![]))))
+            .collect();
+    let params: Vec<String> = param_types.iter().map(|t| substitute(t, &positions).to_string()).collect();
+    format!("{}({})", name, params.join(", "))
+}
+
+/**
+ * Name of a member as written in the source, without the signature of
+ * mangled method names
+ */
+pub fn member_name(key: &str) -> &str {
+    key.split('(').next().unwrap_or(key)
+}
+
+/**
+ * Overloads of a method in a class, in the order they are declared
+ */
+pub fn get_overloads<'a>(cst: &'a ClassSymbolTable, name: &str) -> Vec<(&'a String, &'a CSTEntry)> {
+    let mut overloads: Vec<(&String, &CSTEntry)> = cst.iter()
+            .filter(|(key, entry)| matches!(entry, CSTEntry::Method(..)) && member_name(key) == name)
+            .collect();
+    overloads.sort_by_key(|(_, entry)| entry.get_span().get_start_byte());
+    overloads
+}
+
+/**
+ * Replaces the type params found in `type_args` by the types they stand for
+ */
+pub fn substitute(t: &TypeRef, type_args: &HashMap<String, TypeRef>) -> TypeRef {
+    match t {
+        TypeRef::Named(n) => {
+            if let Some(arg) = type_args.get(n.get_name()) {
+                return arg.clone();
+            }
+            let mut n = n.clone();
+            let args = n.get_type_args().iter().map(|arg| substitute(arg, type_args)).collect();
+            TypeRef::Named(NamedType::new(n.get_span(), String::from(n.get_name()), args))
+        },
+        TypeRef::Closure(c) => {
+            let mut c = c.clone();
+            let params = c.get_params().iter().map(|param| substitute(param, type_args)).collect();
+            let return_type = substitute(c.get_return_type(), type_args);
+            TypeRef::Closure(ClosureType::new(c.get_span(), params, return_type))
+        },
+    }
+}
+
+impl CSTEntry {
+    pub fn get_span(&self) -> Span {
+        match self {
+            CSTEntry::Field(_, span) | CSTEntry::Method(_, _, _, span, _) => *span,
+        }
+    }
 }
 
 /**
  * Thrid and fourth level symbol table.
- * Constains the defined parameters and variables in a function or method.
+ * Constains the defined parameters and variables in a function or method,
+ * as a tree of scopes: the body of the function is the root, the closures
+ * and control flow blocks it contains are its children and so on.
+ */
+#[derive(Clone, Debug)]
+pub struct FunctionSymbolTable {
+    // indexed by scope id, the root first
+    scopes: Vec<Scope>,
+}
+
+/**
+ * Identifies a scope in the symbol table of its function
  */
-pub type FunctionSymbolTable = HashMap<String, FSTEntry>;
+pub type ScopeId = usize;
+
+#[derive(Clone, Debug)]
+pub struct Scope {
+    parent: Option<ScopeId>,
+    kind: ScopeKind,
+    span: Span,
+    symbols: HashMap<String, FSTEntry>,
+    children: Vec<ScopeId>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ScopeKind {
+    Function,
+    Closure,
+    Block,
+}
 
 #[derive(Clone, Debug)]
 pub enum FSTEntry {
-    Param(String, (usize, usize)), // (type, pos)
-    Var(String, (usize, usize)),   // (type, pos)
-    Closure(String, (usize, usize), FunctionSymbolTable), // (return type, pos, FST)
+    Param(Span), // declaration
+    Var(Span),   // declaration
+    Closure(Span, Vec<Capture>), // (declaration, captures)
 }
 
 /**
- * Create a global symbol table from processed ASTs
+ * A param or variable of an enclosing scope used by a closure, or `this`
+ * when the closure is in a method and uses its object
  */
-pub fn create_symbol_table(namespaces: HashMap<String, Vec<SourceFile>>) -> GlobalSymbolTable {
+#[derive(Clone, Debug)]
+pub struct Capture {
+    name: String,
+    // declaration of the param or variable, of the method for `this`
+    declaration: Span,
+    mode: CaptureMode,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CaptureMode {
+    Read,
+    // the closure assigns the variable
+    Mutate,
+}
+
+impl Capture {
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_declaration(&self) -> Span {
+        self.declaration
+    }
+
+    pub fn get_mode(&self) -> CaptureMode {
+        self.mode
+    }
+
+    pub fn set_mode(&mut self, mode: CaptureMode) {
+        self.mode = mode;
+    }
+
+    pub fn new(name: String, declaration: Span, mode: CaptureMode) -> Self {
+        Capture {
+            name,
+            declaration,
+            mode,
+        }
+    }
+}
+
+impl FSTEntry {
+    pub fn get_span(&self) -> Span {
+        match self {
+            FSTEntry::Param(span) | FSTEntry::Var(span) | FSTEntry::Closure(span, _) => *span,
+        }
+    }
+
+    /**
+     * Whether the entry is a param or a variable, the names a function
+     * body can refer to
+     */
+    pub fn is_local(&self) -> bool {
+        matches!(self, FSTEntry::Param(..) | FSTEntry::Var(..))
+    }
+}
+
+impl Scope {
+    pub fn get_symbols(&self) -> &HashMap<String, FSTEntry> {
+        &self.symbols
+    }
+}
+
+impl FunctionSymbolTable {
+    pub const ROOT: ScopeId = 0;
+
+    /**
+     * Symbol table of a function whose body spans `span`, with its root
+     * scope only
+     */
+    pub fn new(span: Span) -> Self {
+        let root = Scope {
+            parent: None,
+            kind: ScopeKind::Function,
+            span,
+            symbols: HashMap::new(),
+            children: vec![],
+        };
+        FunctionSymbolTable { scopes: vec![root] }
+    }
+
+    /**
+     * Creates a scope nested in `parent` and gives its id
+     */
+    pub fn add_scope(&mut self, parent: ScopeId, kind: ScopeKind, span: Span) -> ScopeId {
+        let id = self.scopes.len();
+        self.scopes.push(Scope {
+            parent: Some(parent),
+            kind,
+            span,
+            symbols: HashMap::new(),
+            children: vec![],
+        });
+        self.scopes[parent].children.push(id);
+        id
+    }
+
+    pub fn declare(&mut self, scope: ScopeId, name: &str, entry: FSTEntry) {
+        self.scopes[scope].symbols.insert(String::from(name), entry);
+    }
+
+    pub fn get_scope(&self, scope: ScopeId) -> &Scope {
+        &self.scopes[scope]
+    }
+
+    /**
+     * The scope and the scopes enclosing it, innermost first
+     */
+    pub fn enclosing_scopes(&self, scope: ScopeId) -> Vec<ScopeId> {
+        let mut scopes = vec![scope];
+        while let Some(parent) = self.scopes[*scopes.last().unwrap()].parent {
+            scopes.push(parent);
+        }
+        scopes
+    }
+
+    /**
+     * Closure scopes a use in `scope` of something declared in its
+     * enclosing scope `declared_in` goes through, innermost first
+     */
+    pub fn closures_between(&self, scope: ScopeId, declared_in: ScopeId) -> Vec<ScopeId> {
+        self.enclosing_scopes(scope).into_iter()
+                .take_while(|id| *id != declared_in)
+                .filter(|id| self.scopes[*id].kind == ScopeKind::Closure)
+                .collect()
+    }
+
+    /**
+     * What a closure captures, empty until the captures are collected
+     */
+    pub fn get_captures(&self, closure: ScopeId) -> &[Capture] {
+        let parent = match self.scopes[closure].parent {
+            Some(parent) => parent,
+            None => return &[]
+        };
+        match self.scopes[parent].symbols.get(&closure_name(closure)) {
+            Some(FSTEntry::Closure(_, captures)) => captures,
+            _ => &[]
+        }
+    }
+
+    /**
+     * Records what a closure captures in its entry
+     */
+    pub fn set_captures(&mut self, closure: ScopeId, captures: Vec<Capture>) {
+        let parent = match self.scopes[closure].parent {
+            Some(parent) => parent,
+            None => return
+        };
+        if let Some(FSTEntry::Closure(_, entry_captures)) = self.scopes[parent].symbols.get_mut(&closure_name(closure)) {
+            *entry_captures = captures;
+        }
+    }
+
+    /**
+     * Param or variable a name refers to in a scope, looked up from the
+     * scope outward
+     */
+    pub fn lookup(&self, scope: ScopeId, name: &str) -> Option<(ScopeId, &FSTEntry)> {
+        self.enclosing_scopes(scope).into_iter()
+                .filter_map(|id| self.scopes[id].symbols.get(name).filter(|e| e.is_local()).map(|e| (id, e)))
+                .next()
+    }
+
+    /**
+     * Param or variable a name used at byte `position` of a scope refers
+     * to: the innermost one whose declaration ends before the use
+     */
+    pub fn lookup_before(&self, scope: ScopeId, name: &str, position: usize) -> Option<(ScopeId, &FSTEntry)> {
+        self.enclosing_scopes(scope).into_iter()
+                .filter_map(|id| self.scopes[id].symbols.get(name).map(|e| (id, e)))
+                .find(|(_, e)| e.is_local() && e.get_span().get_end_byte() <= position)
+    }
+
+    /**
+     * Param or variable of an enclosing scope hidden by the declaration of
+     * `name` in `scope`
+     */
+    pub fn find_shadowed(&self, scope: ScopeId, name: &str) -> Option<(ScopeId, &FSTEntry)> {
+        let declaration = self.scopes[scope].symbols.get(name)?;
+        let parent = self.scopes[scope].parent?;
+        self.lookup_before(parent, name, declaration.get_span().get_start_byte())
+    }
+
+    /**
+     * Param or variable of the enclosing scopes of a use at byte `position`
+     * that is only declared after it
+     */
+    pub fn find_declared_after(&self, scope: ScopeId, name: &str, position: usize) -> Option<(ScopeId, &FSTEntry)> {
+        match self.lookup_before(scope, name, position) {
+            Some(_) => None,
+            None => self.lookup(scope, name)
+        }
+    }
+
+    /**
+     * Innermost scope containing the byte `position` of the source
+     */
+    pub fn scope_at(&self, position: usize) -> ScopeId {
+        let contains = |span: Span| span.get_start_byte() <= position && position < span.get_end_byte();
+        let mut current = FunctionSymbolTable::ROOT;
+        while let Some(child) = self.scopes[current].children.iter().find(|child| contains(self.scopes[**child].span)) {
+            current = *child;
+        }
+        current
+    }
+}
+
+/**
+ * Create a global symbol table from processed ASTs.
+ * A name defined twice at the same level keeps its first definition, the
+ * other ones are reported.
+ */
+pub fn create_symbol_table(namespaces: &mut HashMap<String, Vec<SourceFile>>, sink: &mut DiagnosticSink)
+        -> GlobalSymbolTable {
     let mut gst = GlobalSymbolTable::new();
-    for (ns, files) in namespaces {
+    for (ns, files) in namespaces.iter_mut() {
         let mut nst = NamespaceSymbolTable::new();
         {
-            let mut stc = SymbolTableCreator::new(&mut nst);
-            for mut file in files {
+            let mut stc = SymbolTableCreator::new(&mut nst, sink);
+            for file in files {
+                stc.file = String::from(file.get_path());
                 stc.visit_file(file.get_ast());
             }
         }
         // nst no longer borrowed
-        gst.insert(ns, nst);
+        gst.insert(ns.clone(), nst);
     }
     gst
 }
 
 struct SymbolTableCreator<'a> {
     nst: &'a mut NamespaceSymbolTable,
+    sink: &'a mut DiagnosticSink,
+    // where the errors are, file being the path of the visited file
+    namespace: String,
+    file: String,
+    class: Option<String>,
+    function: String,
+    // name -> path of the file defining it, for the entries of the nst
+    nst_files: HashMap<String, String>,
     cst: Option<ClassSymbolTable>,
     fst: Option<FunctionSymbolTable>,
-    closures_fst: Vec<FunctionSymbolTable>,
-    in_closure: bool,
+    // innermost scope of the fst being visited
+    scope: ScopeId,
 }
 
 impl<'a, 'b: 'a> SymbolTableCreator<'a> {
-    pub fn new(nst: &'b mut NamespaceSymbolTable) -> Self {
+    pub fn new(nst: &'b mut NamespaceSymbolTable, sink: &'b mut DiagnosticSink) -> Self {
         SymbolTableCreator {
             nst,
+            sink,
+            namespace: String::from(""),
+            file: String::from(""),
+            class: None,
+            function: String::from(""),
+            nst_files: HashMap::new(),
             cst: None,
             fst: None,
-            closures_fst: vec![],
-            in_closure: false,
+            scope: FunctionSymbolTable::ROOT,
+        }
+    }
+
+    fn error(&self, msg: &str, span: Span) -> Diagnostic {
+        match &self.class {
+            Some(class) => class_member_error(DUPLICATE_DEFINITION, msg, &self.namespace, class, &self.function, span),
+            None => function_error(DUPLICATE_DEFINITION, msg, &self.namespace, &self.function, span),
+        }
+    }
+
+    fn warning(&self, msg: &str, span: Span) -> Diagnostic {
+        match &self.class {
+            Some(class) => class_member_warning(SHADOWED_NAME, msg, &self.namespace, class, &self.function, span),
+            None => function_warning(SHADOWED_NAME, msg, &self.namespace, &self.function, span),
+        }
+    }
+
+    /**
+     * Adds a class or a freestanding function to the namespace
+     */
+    fn define_in_namespace(&mut self, name: &str, entry: NSTEntry) {
+        let (kind, span) = match &entry {
+            NSTEntry::Class(_, _, _, span, _) => ("class", *span),
+            NSTEntry::Fun(_, _, _, span, _) => ("function", *span),
+        };
+        let first = match self.nst.get(name) {
+            Some(NSTEntry::Class(_, _, _, first, _)) | Some(NSTEntry::Fun(_, _, _, first, _)) => *first,
+            None => {
+                self.nst.insert(String::from(name), entry);
+                self.nst_files.insert(String::from(name), self.file.clone());
+                return;
+            }
+        };
+        let first_file = &self.nst_files[name];
+        let msg = if *first_file == self.file {
+            format!("{} `{}` is already defined in this namespace", kind, name)
+        } else {
+            format!("{} `{}` is already defined in this namespace by {}", kind, name, first_file)
+        };
+        let diagnostic = match &entry {
+            NSTEntry::Class(..) => class_error(DUPLICATE_DEFINITION, &msg, &self.namespace, name, span),
+            NSTEntry::Fun(..) => function_error(DUPLICATE_DEFINITION, &msg, &self.namespace, name, span),
+        };
+        self.sink.push(diagnostic.with_secondary(first, "first defined here"));
+    }
+
+    /**
+     * Adds a field or a method to the class being visited, methods being
+     * keyed by their mangled name. A field conflicts with the methods of
+     * the same name.
+     */
+    fn define_in_class(&mut self, name: &str, entry: CSTEntry) {
+        let (kind, key) = match &entry {
+            CSTEntry::Field(..) => ("field", String::from(name)),
+            CSTEntry::Method(type_params, param_types, _, _, _) => ("method", mangle(name, type_params, param_types)),
+        };
+        let span = entry.get_span();
+        let cst = self.cst.as_mut().unwrap();
+        let first = match (&entry, cst.get(&key)) {
+            (_, Some(first)) => first.get_span(),
+            (CSTEntry::Field(..), None) => match get_overloads(cst, name).first() {
+                Some((_, first)) => first.get_span(),
+                None => {
+                    cst.insert(key, entry);
+                    return;
+                }
+            },
+            (CSTEntry::Method(..), None) => match cst.get(name) {
+                Some(first) => first.get_span(),
+                None => {
+                    cst.insert(key, entry);
+                    return;
+                }
+            },
+        };
+        let class = self.class.clone().unwrap_or_default();
+        let msg = format!("{} `{}` is already defined in class {}", kind, key, class);
+        self.sink.push(class_member_error(DUPLICATE_DEFINITION, &msg, &self.namespace, &class, name, span)
+                .with_secondary(first, "first defined here"));
+    }
+
+    /**
+     * Adds a param or a variable to the innermost scope, hiding a param or
+     * variable of an enclosing scope is allowed but reported
+     */
+    fn define_local(&mut self, name: &str, entry: FSTEntry) {
+        let span = entry.get_span();
+        let first = self.fst.as_ref().unwrap().get_scope(self.scope).get_symbols().get(name).map(|e| e.get_span());
+        if let Some(first) = first {
+            let diagnostic = self.error(&format!("`{}` is already defined in this scope", name), span)
+                    .with_secondary(first, "first defined here");
+            self.sink.push(diagnostic);
+            return;
+        }
+        let fst = self.fst.as_mut().unwrap();
+        fst.declare(self.scope, name, entry);
+        let shadowed = fst.find_shadowed(self.scope, name).map(|(_, e)| e.get_span());
+        if let Some(shadowed) = shadowed {
+            let diagnostic = self.warning(&format!("`{}` shadows a variable of an enclosing scope", name), span)
+                    .with_secondary(shadowed, "shadowed declaration here");
+            self.sink.push(diagnostic);
+        }
+    }
+
+    /**
+     * Collects the symbols of a control flow statement in its own scope
+     */
+    fn enter_scope(&mut self, kind: ScopeKind, span: Span) -> ScopeId {
+        let parent = self.scope;
+        self.scope = self.fst.as_mut().unwrap().add_scope(parent, kind, span);
+        parent
+    }
+
+    fn visit_block_statements(&mut self, span: Span, statements: &mut Vec<Statement>) {
+        let parent = self.enter_scope(ScopeKind::Block, statements_span(statements, span));
+        for stmt in statements {
+            self.visit_statement(stmt);
         }
+        self.scope = parent;
+    }
+}
+
+/**
+ * Name of the entry of a closure in its enclosing scope, closures are named
+ * after their scope, which is unique in the function
+ */
+pub fn closure_name(scope: ScopeId) -> String {
+    format!("<closure{}>", scope)
+}
+
+/**
+ * Span of a control flow body, from its first statement to its last one,
+ * empty at the end of the statement `span` when the body is empty
+ */
+fn statements_span(statements: &mut [Statement], span: Span) -> Span {
+    match (statements.first(), statements.last()) {
+        (Some(first), Some(last)) => first.get_span().to(last.get_span()),
+        _ => Span::new(span.get_file_id(), span.get_end_byte(), span.get_end_byte()),
     }
 }
 
 impl<'a> AstVisitor for SymbolTableCreator<'a> {
+    fn visit_file(&mut self, n: &mut File) {
+        self.namespace = String::from(n.get_namespace());
+        for entity in n.get_entities() {
+            match entity {
+                FirstClassEntity::Function(f) => self.visit_function(f),
+                FirstClassEntity::Class(c) => self.visit_class(c),
+            }
+        }
+    }
+
     fn visit_function(&mut self, n: &mut Function) -> () {
-        let fst = FunctionSymbolTable::new();
+        let fst = FunctionSymbolTable::new(n.get_span());
         self.fst = Some(fst);
+        self.scope = FunctionSymbolTable::ROOT;
+        self.function = String::from(n.get_name());
 
         for param in n.get_params() {
             self.visit_param(param);
@@ -102,23 +575,25 @@ impl<'a> AstVisitor for SymbolTableCreator<'a> {
         }
 
         let fst_copy = self.fst.clone().unwrap();
+        let type_params = n.get_type_params().clone();
+        let param_types: Vec<TypeRef> = n.get_params().iter_mut().map(|p| p.get_type().clone()).collect();
+        let return_type = n.get_return_type().clone();
         match &mut self.cst {
-            Some(cst) => {
+            Some(_) => {
                 // if cst is some then we are a method
-                cst.insert(String::from(n.get_name()), CSTEntry::Method(String::from(n.get_return_type()), n.get_pos(), fst_copy));
+                self.define_in_class(n.get_name(), CSTEntry::Method(type_params, param_types, return_type, n.get_span(), fst_copy));
             },
             None => {
                 // if cst is none we are a freestanding fuunction
-                self.nst.insert(String::from(n.get_name()), NSTEntry::Fun(String::from(n.get_return_type()), n.get_pos(), fst_copy));
+                self.define_in_namespace(n.get_name(), NSTEntry::Fun(type_params, param_types, return_type, n.get_span(), fst_copy));
             }
         }
         self.fst = None;
     }
 
     fn visit_closure(&mut self, n: &mut Closure) -> () {
-        let fst = FunctionSymbolTable::new();
-        self.closures_fst.push(fst);
-        self.in_closure = true;
+        let parent = self.enter_scope(ScopeKind::Closure, n.get_span());
+        let closure_scope = self.scope;
 
         for param in n.get_params() {
             self.visit_param(param);
@@ -127,67 +602,80 @@ impl<'a> AstVisitor for SymbolTableCreator<'a> {
             self.visit_statement(stmt);
         }
 
-        let fst_copy = self.closures_fst.last().unwrap().clone();
-        let name = format!("<closure{}>", self.closures_fst.len());
-        let entry = FSTEntry::Closure(String::from(n.get_return_type()), n.get_pos(), fst_copy);
-        if self.closures_fst.len() == 1 {
-            if let Some(cur_fst) = &mut self.fst {
-                cur_fst.insert(name, entry);
-            }
-        } else {
-            let i = self.closures_fst.len() - 1;
-            self.closures_fst[i].insert(name, entry);
+        self.scope = parent;
+        let name = closure_name(closure_scope);
+        // captures are known once the whole function is collected
+        let entry = FSTEntry::Closure(n.get_span(), vec![]);
+        self.fst.as_mut().unwrap().declare(parent, &name, entry);
+    }
+
+    fn visit_if_statement(&mut self, n: &mut IfStatement) -> () {
+        let span = n.get_span();
+        self.visit_expression(n.get_condition());
+        self.visit_block_statements(span, n.get_statements());
+        if let Some(else_statements) = n.get_else_statements() {
+            self.visit_block_statements(span, else_statements);
         }
+    }
 
-        self.closures_fst.pop();
-        self.in_closure = self.closures_fst.len() == 0;
+    fn visit_while_statement(&mut self, n: &mut WhileStatement) -> () {
+        let span = n.get_span();
+        self.visit_expression(n.get_condition());
+        self.visit_block_statements(span, n.get_statements());
     }
 
-    fn visit_param(&mut self, n: &mut Param) -> () {
-        match &mut self.fst {
-            Some(fst) => {
-                if self.in_closure {
-                    let i = self.closures_fst.len() - 1;
-                    self.closures_fst[i].insert(String::from(n.get_name()), FSTEntry::Param(String::from(n.get_type()), n.get_pos()));
-                } else {
-                    fst.insert(String::from(n.get_name()), FSTEntry::Param(String::from(n.get_type()), n.get_pos()));
-                }
-            },
-            None => unreachable!()
+    fn visit_for_statement(&mut self, n: &mut ForStatement) -> () {
+        // the loop variable is only visible in the loop
+        let parent = self.enter_scope(ScopeKind::Block, n.get_span());
+        if let Some(init) = n.get_init() {
+            self.visit_statement(init);
+        }
+        if let Some(condition) = n.get_condition() {
+            self.visit_expression(condition);
+        }
+        if let Some(update) = n.get_update() {
+            self.visit_statement(update);
         }
+        for stmt in n.get_statements() {
+            self.visit_statement(stmt);
+        }
+        self.scope = parent;
+    }
+
+    fn visit_param(&mut self, n: &mut Param) -> () {
+        let entry = FSTEntry::Param(n.get_span());
+        self.define_local(n.get_name(), entry);
     }
 
     fn visit_variable_declaration(&mut self, n: &mut VariableDeclaration) -> () {
-        match &mut self.fst {
-            Some(fst) => {
-                if self.in_closure {
-                    let i = self.closures_fst.len() - 1;
-                    self.closures_fst[i].insert(String::from(n.get_name()), FSTEntry::Var(String::from(n.get_type_name()), n.get_pos()));
-                } else {
-                    fst.insert(String::from(n.get_name()), FSTEntry::Var(String::from(n.get_type_name()), n.get_pos()));
-                }
-            },
-            None => unreachable!()
+        if let Some(e) = n.get_value() {
+            self.visit_expression(e);
         }
+        let entry = FSTEntry::Var(n.get_span());
+        self.define_local(n.get_name(), entry);
     }
 
     fn visit_class(&mut self, n: &mut Class) -> () {
         let cst = ClassSymbolTable::new();
         self.cst = Some(cst);
+        self.class = Some(String::from(n.get_name()));
 
         for member in n.get_members() {
             self.visit_class_member(member);
         }
         let cst_copy = self.cst.clone().unwrap();
-        self.nst.insert(String::from(n.get_name()),
-                NSTEntry::Class(String::from(n.get_super_name()), n.get_implemented_interfaces().clone(), n.get_pos(), cst_copy));
+        let entry = NSTEntry::Class(n.get_type_params().clone(), n.get_super_class().clone(),
+                n.get_implemented_interfaces().clone(), n.get_span(), cst_copy);
+        self.define_in_namespace(n.get_name(), entry);
         self.cst = None;
+        self.class = None;
     }
 
     fn visit_field(&mut self, n: &mut Field) -> () {
         match &mut self.cst {
-            Some(cst) => {
-                cst.insert(String::from(n.get_name()), CSTEntry::Field(String::from(n.get_type_name()), n.get_pos()));
+            Some(_) => {
+                let entry = CSTEntry::Field(n.get_type().clone(), n.get_span());
+                self.define_in_class(n.get_name(), entry);
             },
             None => unreachable!()
         }
@@ -1,10 +1,11 @@
 use super::node::FromPair;
 use super::super::parser::Rule;
+use super::super::span::{Span, FileId};
 use pest::iterators::Pair;
 
 #[derive(Clone, Debug)]
 pub struct Attribute {
-    pos: (usize, usize),
+    span: Span,
     name: String,
 }
 
@@ -13,28 +14,28 @@ impl Attribute {
         &self.name
     }
 
-    pub fn new(pos: (usize, usize), name: String) -> Self {
+    pub fn new(span: Span, name: String) -> Self {
         Attribute {
-            pos,
+            span,
             name,
         }
     }
 }
 
 impl<'a> FromPair<'a> for Attribute {
-    fn from_pair<'b>(pair: Pair<'b, Rule>) -> Self {
+    fn from_pair<'b>(pair: Pair<'b, Rule>, file_id: FileId) -> Self {
         assert_eq!(pair.as_rule(), Rule::attribute);
         
-        let pos = pair.as_span().start_pos().line_col();
+        let span = Span::from_pair(&pair, file_id);
         let inner_pair = pair.into_inner().next().unwrap();
 
         Attribute {
-            pos,
+            span,
             name: String::from(inner_pair.as_str())
         }
     }
 
-    fn get_pos(&self) -> (usize, usize) {
-        self.pos
+    fn get_span(&self) -> Span {
+        self.span
     }
 }
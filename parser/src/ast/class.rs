@@ -1,16 +1,19 @@
 use super::attribute::Attribute;
 use super::function::Function;
+use super::type_ref::{TypeRef, TypeParam};
 use super::node::FromPair;
 use super::super::parser::Rule;
+use super::super::span::{Span, FileId};
 use pest::iterators::Pair;
 
 #[derive(Clone, Debug)]
 pub struct Class {
-    pos: (usize, usize),
+    span: Span,
     attributes: Vec<Attribute>,
     name: String,
-    super_name: String,
-    implemented_interfaces: Vec<String>,
+    type_params: Vec<TypeParam>,
+    super_class: TypeRef,
+    implemented_interfaces: Vec<TypeRef>,
     members: Vec<ClassMember>,
 }
 
@@ -23,15 +26,15 @@ pub enum ClassMember {
 
 #[derive(Clone, Debug)]
 pub struct Field {
-    pos: (usize, usize),
+    span: Span,
     attributes: Vec<Attribute>,
     name: String,
-    type_name: String,
+    field_type: TypeRef,
 }
 
 #[derive(Clone, Debug)]
 pub struct Block {
-    pos: (usize, usize),
+    span: Span,
     attributes: Vec<Attribute>,
     members: Vec<ClassMember>,
 }
@@ -45,11 +48,15 @@ impl Class {
         &self.name
     }
 
-    pub fn get_super_name(&self) -> &str {
-        &self.super_name
+    pub fn get_type_params(&mut self) -> &mut Vec<TypeParam> {
+        &mut self.type_params
     }
 
-    pub fn get_implemented_interfaces(&mut self) -> &mut Vec<String> {
+    pub fn get_super_class(&mut self) -> &mut TypeRef {
+        &mut self.super_class
+    }
+
+    pub fn get_implemented_interfaces(&mut self) -> &mut Vec<TypeRef> {
         &mut self.implemented_interfaces
     }
 
@@ -57,14 +64,15 @@ impl Class {
         &mut self.members
     }
 
-    pub fn new(pos: (usize, usize),  attributes: Vec<Attribute>, name: String,
-            super_name: String, implemented_interfaces: Vec<String>,
-            members: Vec<ClassMember>) -> Self {
+    pub fn new(span: Span,  attributes: Vec<Attribute>, name: String,
+            type_params: Vec<TypeParam>, super_class: TypeRef,
+            implemented_interfaces: Vec<TypeRef>, members: Vec<ClassMember>) -> Self {
         Class {
-            pos,
+            span,
             attributes,
             name,
-            super_name,
+            type_params,
+            super_class,
             implemented_interfaces,
             members,
         }
@@ -72,52 +80,60 @@ impl Class {
 }
 
 impl<'a> FromPair<'a> for Class {
-    fn from_pair<'b>(pair: Pair<'b, Rule>) -> Self {
+    fn from_pair<'b>(pair: Pair<'b, Rule>, file_id: FileId) -> Self {
         assert_eq!(pair.as_rule(), Rule::class_decl);
 
-        let pos = pair.as_span().start_pos().line_col();
+        let span = Span::from_pair(&pair, file_id);
         let mut inner_iter = pair.into_inner();
         let attributes_iter = inner_iter.next().unwrap().into_inner();
         let mut attributes: Vec<Attribute> = vec![];
-        let mut implemented_interfaces: Vec<String> = vec![];
+        let mut type_params: Vec<TypeParam> = vec![];
+        let mut super_class: Option<TypeRef> = None;
+        let mut implemented_interfaces: Vec<TypeRef> = vec![];
         let mut members: Vec<ClassMember> = vec![];
         let name = String::from(inner_iter.next().unwrap().as_str());
-        let super_name = String::from(inner_iter.next().unwrap().as_str());
         for attr in attributes_iter {
-            attributes.push(Attribute::from_pair(attr));
+            attributes.push(Attribute::from_pair(attr, file_id));
         }
         for pair in inner_iter {
             match pair.as_rule() {
+                Rule::type_params => {
+                    type_params = TypeParam::from_type_params(pair, file_id);
+                },
+                Rule::extended_class => {
+                    super_class = Some(TypeRef::from_pair(pair.into_inner().next().unwrap(), file_id));
+                },
                 Rule::interface_list => {
                     for interface in pair.into_inner() {
-                        implemented_interfaces.push(String::from(interface.as_str()));
+                        implemented_interfaces.push(TypeRef::from_pair(interface, file_id));
                     }
                 },
                 Rule::field_decl => {
-                    members.push(ClassMember::Field(Field::from_pair(pair)));
+                    members.push(ClassMember::Field(Field::from_pair(pair, file_id)));
                 },
                 Rule::method_decl => {
-                    members.push(ClassMember::Method(Function::from_pair(pair)));
+                    members.push(ClassMember::Method(Function::from_pair(pair, file_id)));
                 },
                 Rule::block_decl => {
-                    members.push(ClassMember::Block(Block::from_pair(pair)));
+                    members.push(ClassMember::Block(Block::from_pair(pair, file_id)));
                 },
                 _ => unreachable!()
             }
         }
 
         Class {
-            pos,
+            span,
             attributes,
             name,
-            super_name,
+            type_params,
+            super_class: super_class.unwrap(),
             implemented_interfaces,
             members,
         }
     }
 
-    fn get_pos(&self) -> (usize, usize) {
-        self.pos
+    fn get_span(&self) -> Span {
+        self.span
     }
 }
 
@@ -130,45 +146,45 @@ impl Field {
         &self.name
     }
 
-    pub fn get_type_name(&self) -> &str {
-        &self.type_name
+    pub fn get_type(&mut self) -> &mut TypeRef {
+        &mut self.field_type
     }
 
-    pub fn new(pos: (usize, usize), attributes: Vec<Attribute>, name: String,
-            type_name: String) -> Self {
+    pub fn new(span: Span, attributes: Vec<Attribute>, name: String,
+            field_type: TypeRef) -> Self {
         Field {
-            pos,
+            span,
             attributes,
             name,
-            type_name,
+            field_type,
         }
     }
 }
 
 impl<'a> FromPair<'a> for Field {
-    fn from_pair<'b>(pair: Pair<'b, Rule>) -> Self {
+    fn from_pair<'b>(pair: Pair<'b, Rule>, file_id: FileId) -> Self {
         assert_eq!(pair.as_rule(), Rule::field_decl);
 
-        let pos = pair.as_span().start_pos().line_col();
+        let span = Span::from_pair(&pair, file_id);
         let mut inner_iter = pair.into_inner();
         let mut attributes: Vec<Attribute> = vec![];
         let attributes_iter = inner_iter.next().unwrap().into_inner();
         for attr in attributes_iter {
-            attributes.push(Attribute::from_pair(attr));
+            attributes.push(Attribute::from_pair(attr, file_id));
         }
         let name = String::from(inner_iter.next().unwrap().as_str());
-        let type_name = String::from(inner_iter.next().unwrap().as_str());
+        let field_type = TypeRef::from_pair(inner_iter.next().unwrap(), file_id);
 
         Field {
-            pos,
+            span,
             attributes,
             name,
-            type_name,
+            field_type,
         }
     }
 
-    fn get_pos(&self) -> (usize, usize) {
-        self.pos
+    fn get_span(&self) -> Span {
+        self.span
     }
 }
 
@@ -181,10 +197,10 @@ impl<'a> Block {
         &mut self.members
     }
 
-    pub fn new(pos: (usize, usize), attributes: Vec<Attribute>,
+    pub fn new(span: Span, attributes: Vec<Attribute>,
             members: Vec<ClassMember>) -> Self {
         Block {
-            pos,
+            span,
             attributes,
             members,
         }
@@ -192,37 +208,37 @@ impl<'a> Block {
 }
 
 impl<'a> FromPair<'a> for Block {
-    fn from_pair<'b>(pair: Pair<'b, Rule>) -> Self {
+    fn from_pair<'b>(pair: Pair<'b, Rule>, file_id: FileId) -> Self {
         assert_eq!(pair.as_rule(), Rule::block_decl);
 
-        let pos = pair.as_span().start_pos().line_col();
+        let span = Span::from_pair(&pair, file_id);
         let mut inner_iter = pair.into_inner();
         let attributes_iter = inner_iter.next().unwrap().into_inner();
         let mut attributes: Vec<Attribute> = vec![];
         let mut members: Vec<ClassMember> = vec![];
         for attr in attributes_iter {
-            attributes.push(Attribute::from_pair(attr));
+            attributes.push(Attribute::from_pair(attr, file_id));
         }
         for pair in inner_iter {
             match pair.as_rule() {
                 Rule::field_decl => {
-                    members.push(ClassMember::Field(Field::from_pair(pair)));
+                    members.push(ClassMember::Field(Field::from_pair(pair, file_id)));
                 },
                 Rule::method_decl => {
-                    members.push(ClassMember::Method(Function::from_pair(pair)));
+                    members.push(ClassMember::Method(Function::from_pair(pair, file_id)));
                 },
                 _ => unreachable!()
             }
         }
 
         Block {
-            pos,
+            span,
             attributes,
             members,
         }
     }
 
-    fn get_pos(&self) -> (usize, usize) {
-        self.pos
+    fn get_span(&self) -> Span {
+        self.span
     }
 }
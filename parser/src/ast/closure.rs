@@ -1,14 +1,16 @@
 use super::node::FromPair;
 use super::param::Param;
 use super::statement::Statement;
+use super::type_ref::TypeRef;
 use super::super::parser::Rule;
+use super::super::span::{Span, FileId};
 use pest::iterators::Pair;
 
 #[derive(Clone, Debug)]
 pub struct Closure {
-    pos: (usize, usize),
+    span: Span,
     params: Vec<Param>,
-    return_type: String,
+    return_type: Option<TypeRef>,
     statements: Vec<Statement>,
 }
 
@@ -17,18 +19,21 @@ impl Closure {
         &mut self.params
     }
 
-    pub fn get_return_type(&self) -> &str {
-        &self.return_type
+    /**
+     * None when the closure is written without its signature: `{ ... }`
+     */
+    pub fn get_return_type(&mut self) -> &mut Option<TypeRef> {
+        &mut self.return_type
     }
 
     pub fn get_statements(&mut self) ->&mut Vec<Statement> {
         &mut self.statements
     }
 
-    pub fn new(pos: (usize, usize), params: Vec<Param>, return_type: String,
+    pub fn new(span: Span, params: Vec<Param>, return_type: Option<TypeRef>,
             statements: Vec<Statement>) -> Self {
         Closure {
-            pos,
+            span,
             params,
             return_type,
             statements,
@@ -37,27 +42,27 @@ impl Closure {
 }
 
 impl<'a> FromPair<'a> for Closure {
-    fn from_pair<'b>(pair: Pair<'b, Rule>) -> Self {
+    fn from_pair<'b>(pair: Pair<'b, Rule>, file_id: FileId) -> Self {
         assert_eq!(pair.as_rule(), Rule::closure);
 
-        let pos = pair.as_span().start_pos().line_col();
+        let span = Span::from_pair(&pair, file_id);
         let mut params: Vec<Param> = vec![];
-        let mut return_type = String::new();
+        let mut return_type: Option<TypeRef> = None;
         let mut statements: Vec<Statement> = vec![];
 
         for pair in pair.into_inner() {
             match pair.as_rule() {
                 Rule::param_list => {
                     for param in pair.into_inner() {
-                        params.push(Param::from_pair(param));
+                        params.push(Param::from_pair(param, file_id));
                     }
                 },
-                Rule::_type => {
-                    return_type.insert_str(0, pair.as_str());
+                Rule::type_ref => {
+                    return_type = Some(TypeRef::from_pair(pair, file_id));
                 },
                 Rule::executable_body => {
                     for statement in pair.into_inner() {
-                        statements.push(Statement::from_pair(statement));
+                        statements.push(Statement::from_pair(statement, file_id));
                     }
                 },
                 _ => {}
@@ -65,14 +70,14 @@ impl<'a> FromPair<'a> for Closure {
         }
 
         Closure {
-            pos,
+            span,
             params,
             return_type,
             statements
         }
     }
 
-    fn get_pos(&self) -> (usize, usize) {
-        self.pos
+    fn get_span(&self) -> Span {
+        self.span
     }
 }
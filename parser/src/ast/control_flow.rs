@@ -0,0 +1,228 @@
+use super::node::FromPair;
+use super::expression::{Expression, QualifiedExpression};
+use super::statement::Statement;
+use super::variable::{VariableDeclaration, VariableAffectation};
+use super::super::parser::Rule;
+use super::super::span::{Span, FileId};
+use pest::iterators::Pair;
+
+#[derive(Clone, Debug)]
+pub struct IfStatement {
+    span: Span,
+    condition: Expression,
+    statements: Vec<Statement>,
+    else_statements: Option<Vec<Statement>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct WhileStatement {
+    span: Span,
+    condition: Expression,
+    statements: Vec<Statement>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ForStatement {
+    span: Span,
+    init: Option<Box<Statement>>,
+    condition: Option<Expression>,
+    update: Option<Box<Statement>>,
+    statements: Vec<Statement>,
+}
+
+fn statements_from_body(pair: Pair<Rule>, file_id: FileId) -> Vec<Statement> {
+    assert_eq!(pair.as_rule(), Rule::executable_body);
+
+    let mut statements: Vec<Statement> = vec![];
+    for statement in pair.into_inner() {
+        statements.push(Statement::from_pair(statement, file_id));
+    }
+
+    statements
+}
+
+impl IfStatement {
+    pub fn get_condition(&mut self) -> &mut Expression {
+        &mut self.condition
+    }
+
+    pub fn get_statements(&mut self) -> &mut Vec<Statement> {
+        &mut self.statements
+    }
+
+    /**
+     * `else if` branches are stored as an else body made of a single
+     * if statement
+     */
+    pub fn get_else_statements(&mut self) -> &mut Option<Vec<Statement>> {
+        &mut self.else_statements
+    }
+
+    pub fn new(span: Span, condition: Expression, statements: Vec<Statement>,
+            else_statements: Option<Vec<Statement>>) -> Self {
+        IfStatement {
+            span,
+            condition,
+            statements,
+            else_statements,
+        }
+    }
+}
+
+impl<'a> FromPair<'a> for IfStatement {
+    fn from_pair<'b>(pair: Pair<'b, Rule>, file_id: FileId) -> Self {
+        assert_eq!(pair.as_rule(), Rule::if_stmt);
+
+        let span = Span::from_pair(&pair, file_id);
+        let mut inner_iter = pair.into_inner();
+        let condition = Expression::from_pair(inner_iter.next().unwrap(), file_id);
+        let statements = statements_from_body(inner_iter.next().unwrap(), file_id);
+        let else_statements = match inner_iter.next() {
+            Some(pair) => match pair.as_rule() {
+                Rule::if_stmt => Some(vec![Statement::If(IfStatement::from_pair(pair, file_id))]),
+                _ => Some(statements_from_body(pair, file_id)),
+            },
+            None => None
+        };
+
+        IfStatement {
+            span,
+            condition,
+            statements,
+            else_statements,
+        }
+    }
+
+    fn get_span(&self) -> Span {
+        self.span
+    }
+}
+
+impl WhileStatement {
+    pub fn get_condition(&mut self) -> &mut Expression {
+        &mut self.condition
+    }
+
+    pub fn get_statements(&mut self) -> &mut Vec<Statement> {
+        &mut self.statements
+    }
+
+    pub fn new(span: Span, condition: Expression, statements: Vec<Statement>) -> Self {
+        WhileStatement {
+            span,
+            condition,
+            statements,
+        }
+    }
+}
+
+impl<'a> FromPair<'a> for WhileStatement {
+    fn from_pair<'b>(pair: Pair<'b, Rule>, file_id: FileId) -> Self {
+        assert_eq!(pair.as_rule(), Rule::while_stmt);
+
+        let span = Span::from_pair(&pair, file_id);
+        let mut inner_iter = pair.into_inner();
+
+        WhileStatement {
+            span,
+            condition: Expression::from_pair(inner_iter.next().unwrap(), file_id),
+            statements: statements_from_body(inner_iter.next().unwrap(), file_id),
+        }
+    }
+
+    fn get_span(&self) -> Span {
+        self.span
+    }
+}
+
+impl ForStatement {
+    /**
+     * Either a declaration or an affectation
+     */
+    pub fn get_init(&mut self) -> &mut Option<Box<Statement>> {
+        &mut self.init
+    }
+
+    pub fn get_condition(&mut self) -> &mut Option<Expression> {
+        &mut self.condition
+    }
+
+    /**
+     * Either an affectation or a qualified expression
+     */
+    pub fn get_update(&mut self) -> &mut Option<Box<Statement>> {
+        &mut self.update
+    }
+
+    pub fn get_statements(&mut self) -> &mut Vec<Statement> {
+        &mut self.statements
+    }
+
+    pub fn new(span: Span, init: Option<Box<Statement>>, condition: Option<Expression>,
+            update: Option<Box<Statement>>, statements: Vec<Statement>) -> Self {
+        ForStatement {
+            span,
+            init,
+            condition,
+            update,
+            statements,
+        }
+    }
+}
+
+impl<'a> FromPair<'a> for ForStatement {
+    fn from_pair<'b>(pair: Pair<'b, Rule>, file_id: FileId) -> Self {
+        assert_eq!(pair.as_rule(), Rule::for_stmt);
+
+        let span = Span::from_pair(&pair, file_id);
+        let mut init: Option<Box<Statement>> = None;
+        let mut condition: Option<Expression> = None;
+        let mut update: Option<Box<Statement>> = None;
+        let mut statements: Vec<Statement> = vec![];
+
+        for pair in pair.into_inner() {
+            match pair.as_rule() {
+                Rule::for_init | Rule::for_update => {
+                    let is_init = pair.as_rule() == Rule::for_init;
+                    let inner_pair = pair.into_inner().next().unwrap();
+                    let statement = match inner_pair.as_rule() {
+                        Rule::declaration => {
+                            Statement::Declaration(VariableDeclaration::from_pair(inner_pair, file_id))
+                        },
+                        Rule::affectation => {
+                            Statement::Affectation(VariableAffectation::from_pair(inner_pair, file_id))
+                        },
+                        Rule::qualified_expression => {
+                            Statement::QualifiedExpression(QualifiedExpression::from_pair(inner_pair, file_id))
+                        },
+                        _ => unreachable!()
+                    };
+                    if is_init {
+                        init = Some(Box::new(statement));
+                    } else {
+                        update = Some(Box::new(statement));
+                    }
+                },
+                Rule::for_condition => {
+                    condition = Some(Expression::from_pair(pair.into_inner().next().unwrap(), file_id));
+                },
+                Rule::executable_body => {
+                    statements = statements_from_body(pair, file_id);
+                },
+                _ => unreachable!()
+            }
+        }
+
+        ForStatement {
+            span,
+            init,
+            condition,
+            update,
+            statements,
+        }
+    }
+
+    fn get_span(&self) -> Span {
+        self.span
+    }
+}
@@ -1,20 +1,30 @@
 use super::node::FromPair;
 use super::closure::Closure;
 use super::function::FunctionCall;
-use super::litterals::{Identifier, Integer, StringLitteral, Char, Boolean};
+use super::litterals::{
+    Identifier, Integer, Float, StringLitteral, Char, Boolean, ArrayLitteral, MapLitteral
+};
 use super::super::parser::Rule;
+use super::super::span::{Span, FileId};
 use pest::iterators::{Pair, Pairs};
 use pest::prec_climber::{Assoc::*, Operator, PrecClimber};
 
 lazy_static! {
     static ref PREC_CLIMBER: PrecClimber<Rule> = {
         PrecClimber::new(vec![
+            Operator::new(Rule::or_op, Left),
+            Operator::new(Rule::and_op, Left),
+            Operator::new(Rule::bor_op, Left),
+            Operator::new(Rule::bxor_op, Left),
+            Operator::new(Rule::band_op, Left),
             Operator::new(Rule::eq_op, Left)
             | Operator::new(Rule::ne_op, Left),
             Operator::new(Rule::gt_op, Left)
             | Operator::new(Rule::ge_op, Left)
             | Operator::new(Rule::lt_op, Left)
             | Operator::new(Rule::le_op, Left),
+            Operator::new(Rule::shl_op, Left)
+            | Operator::new(Rule::shr_op, Left),
             Operator::new(Rule::ad_op, Left)
             | Operator::new(Rule::mn_op, Left),
             Operator::new(Rule::tm_op, Left)
@@ -29,28 +39,44 @@ pub enum QualifiedExpressionPart {
     MethodCall(FunctionCall),
     Identifier(Identifier),
     Integer(Integer),
+    Float(Float),
     StringLitteral(StringLitteral),
     Char(Char),
     Boolean(Boolean),
-    Null,
+    Null { span: Span },
     Closure(Closure),
     ParenExpr(Expression),
+    Index(Index),
+    Array(ArrayLitteral),
+    Map(MapLitteral),
+}
+
+/**
+ * `[expr]` following a qualified expression part, calls the `operator[]`
+ * method of the indexed object or `operator[]=` when assigned
+ */
+#[derive(Clone, Debug)]
+pub struct Index {
+    span: Span,
+    expr: Expression,
 }
 
 #[derive(Clone, Debug)]
 pub struct QualifiedExpression {
-    pos: (usize, usize),
+    span: Span,
     parts: Vec<QualifiedExpressionPart>,
 }
 
 #[derive(Clone, Debug)]
 pub enum Expression {
     Operation(Operation),
+    Unary(UnaryOperation),
     Expr(QualifiedExpression),
 }
 
 #[derive(Clone, Debug)]
 pub struct Operation {
+    span: Span,
     lval: Box<Expression>,
     rval: Box<Expression>,
     op: OperationType,
@@ -58,12 +84,19 @@ pub struct Operation {
 
 #[derive(Clone, Debug)]
 pub enum OperationType {
+    Or,
+    And,
+    BitwiseOr,
+    BitwiseXor,
+    BitwiseAnd,
     Eqal,
     NotEqual,
     GreaterOrEqual,
     LowerOrEqual,
     GreaterThan,
     LowerThan,
+    ShiftLeft,
+    ShiftRight,
     Add,
     Minus,
     Times,
@@ -71,9 +104,27 @@ pub enum OperationType {
     Mod,
 }
 
+#[derive(Clone, Debug)]
+pub struct UnaryOperation {
+    span: Span,
+    expr: Box<Expression>,
+    op: UnaryOperationType,
+}
+
+#[derive(Clone, Debug)]
+pub enum UnaryOperationType {
+    Not,
+    Negate,
+    BitwiseNot,
+}
+
 impl Operation {
+    /**
+     * The span goes from the start of `lval` to the end of `rval`
+     */
     pub fn new(lval: Expression, rval: Expression, op: OperationType) -> Self {
         Operation {
+            span: lval.get_span().to(rval.get_span()),
             lval: Box::new(lval),
             rval: Box::new(rval),
             op,
@@ -91,12 +142,79 @@ impl Operation {
     pub fn get_op(&mut self) -> &mut OperationType {
         &mut self.op
     }
+
+    pub fn get_span(&self) -> Span {
+        self.span
+    }
+}
+
+impl OperationType {
+    /**
+     * `&&` and `||` don't evaluate their right operand when the left one
+     * is enough to know the result
+     */
+    pub fn is_short_circuit(&self) -> bool {
+        matches!(self, OperationType::Or | OperationType::And)
+    }
+}
+
+impl UnaryOperation {
+    pub fn new(span: Span, expr: Expression, op: UnaryOperationType) -> Self {
+        UnaryOperation {
+            span,
+            expr: Box::new(expr),
+            op,
+        }
+    }
+
+    pub fn get_expr(&mut self) -> &mut Expression {
+        &mut self.expr
+    }
+
+    pub fn get_op(&mut self) -> &mut UnaryOperationType {
+        &mut self.op
+    }
+
+    pub fn get_span(&self) -> Span {
+        self.span
+    }
+
+    /**
+     * Builds the operations of an unary_expression, the operator closest to
+     * the operand being the innermost one
+     */
+    fn from_unary_expression(pair: Pair<Rule>, file_id: FileId) -> Expression {
+        assert_eq!(pair.as_rule(), Rule::unary_expression);
+
+        let mut ops: Vec<(UnaryOperationType, Span)> = vec![];
+        let mut expr: Option<Expression> = None;
+        for pair in pair.into_inner() {
+            let span = Span::from_pair(&pair, file_id);
+            match pair.as_rule() {
+                Rule::not_op  => ops.push((UnaryOperationType::Not, span)),
+                Rule::neg_op  => ops.push((UnaryOperationType::Negate, span)),
+                Rule::bnot_op => ops.push((UnaryOperationType::BitwiseNot, span)),
+                Rule::qualified_expression => {
+                    expr = Some(Expression::Expr(QualifiedExpression::from_pair(pair, file_id)));
+                },
+                _ => unreachable!()
+            }
+        }
+
+        let mut expr = expr.unwrap();
+        // each operation goes from its operator to the end of the operand
+        while let Some((op, span)) = ops.pop() {
+            let span = span.to(expr.get_span());
+            expr = Expression::Unary(UnaryOperation::new(span, expr, op));
+        }
+        expr
+    }
 }
 
 impl QualifiedExpression {
-    pub fn new(pos: (usize, usize), parts: Vec<QualifiedExpressionPart>) -> Self {
+    pub fn new(span: Span, parts: Vec<QualifiedExpressionPart>) -> Self {
         QualifiedExpression {
-            pos,
+            span,
             parts,
         }
     }
@@ -106,79 +224,121 @@ impl QualifiedExpression {
     }
 }
 impl<'a> FromPair<'a> for QualifiedExpression {
-    fn from_pair<'b>(pair: Pair<'b, Rule>) -> Self {
+    fn from_pair<'b>(pair: Pair<'b, Rule>, file_id: FileId) -> Self {
         if pair.as_rule() != Rule::qualified_expression
                 && pair.as_rule() != Rule::qualified_ident {
             panic!("Cannot build QualifiedExpression from rule: {:#?}", pair.as_rule());
         }
 
         let mut parts: Vec<QualifiedExpressionPart> = vec![];
-        let pos = pair.as_span().start_pos().line_col();
+        let span = Span::from_pair(&pair, file_id);
         for pair in pair.into_inner() {
-            parts.push(QualifiedExpressionPart::from_pair(pair));
+            parts.push(QualifiedExpressionPart::from_pair(pair, file_id));
         }
 
         QualifiedExpression {
-            pos,
+            span,
             parts,
         }
     }
 
-    fn get_pos(&self) -> (usize, usize) {
-        self.pos
+    fn get_span(&self) -> Span {
+        self.span
+    }
+}
+
+impl Index {
+    pub fn get_expr(&mut self) -> &mut Expression {
+        &mut self.expr
+    }
+
+    pub fn new(span: Span, expr: Expression) -> Self {
+        Index {
+            span,
+            expr,
+        }
+    }
+}
+
+impl<'a> FromPair<'a> for Index {
+    fn from_pair<'b>(pair: Pair<'b, Rule>, file_id: FileId) -> Self {
+        assert_eq!(pair.as_rule(), Rule::index);
+
+        let span = Span::from_pair(&pair, file_id);
+
+        Index {
+            span,
+            expr: Expression::from_pair(pair.into_inner().next().unwrap(), file_id),
+        }
+    }
+
+    fn get_span(&self) -> Span {
+        self.span
     }
 }
 
 impl<'a> FromPair<'a> for QualifiedExpressionPart {
-    fn from_pair<'b>(pair: Pair<'b, Rule>) -> Self {
+    fn from_pair<'b>(pair: Pair<'b, Rule>, file_id: FileId) -> Self {
         match pair.as_rule() {
-            Rule::ident       => QualifiedExpressionPart::Identifier(Identifier::from_pair(pair)),
-            Rule::method_call => QualifiedExpressionPart::MethodCall(FunctionCall::from_pair(pair)),
-            Rule::integer     => QualifiedExpressionPart::Integer(Integer::from_pair(pair)),
-            Rule::string      =>  QualifiedExpressionPart::StringLitteral(StringLitteral::from_pair(pair)),
-            Rule::character   => QualifiedExpressionPart::Char(Char::from_pair(pair)),
-            Rule::boolean     => QualifiedExpressionPart::Boolean(Boolean::from_pair(pair)),
-            Rule::null        => QualifiedExpressionPart::Null,
-            Rule::closure     => QualifiedExpressionPart::Closure(Closure::from_pair(pair)),
-            Rule::expression  => QualifiedExpressionPart::ParenExpr(Expression::from_pair(pair)),
+            Rule::ident       => QualifiedExpressionPart::Identifier(Identifier::from_pair(pair, file_id)),
+            Rule::method_call => QualifiedExpressionPart::MethodCall(FunctionCall::from_pair(pair, file_id)),
+            Rule::integer     => QualifiedExpressionPart::Integer(Integer::from_pair(pair, file_id)),
+            Rule::float       => QualifiedExpressionPart::Float(Float::from_pair(pair, file_id)),
+            Rule::string      =>  QualifiedExpressionPart::StringLitteral(StringLitteral::from_pair(pair, file_id)),
+            Rule::character   => QualifiedExpressionPart::Char(Char::from_pair(pair, file_id)),
+            Rule::boolean     => QualifiedExpressionPart::Boolean(Boolean::from_pair(pair, file_id)),
+            Rule::null        => QualifiedExpressionPart::Null { span: Span::from_pair(&pair, file_id) },
+            Rule::closure     => QualifiedExpressionPart::Closure(Closure::from_pair(pair, file_id)),
+            Rule::expression  => QualifiedExpressionPart::ParenExpr(Expression::from_pair(pair, file_id)),
+            Rule::index       => QualifiedExpressionPart::Index(Index::from_pair(pair, file_id)),
+            Rule::array       => QualifiedExpressionPart::Array(ArrayLitteral::from_pair(pair, file_id)),
+            Rule::map         => QualifiedExpressionPart::Map(MapLitteral::from_pair(pair, file_id)),
             _                 => unreachable!()
         }
     }
 
-    fn get_pos(&self) -> (usize, usize) {
+    fn get_span(&self) -> Span {
         match self {
-            QualifiedExpressionPart::Identifier(i)     => i.get_pos(),
-            QualifiedExpressionPart::MethodCall(f)     => f.get_pos(),
-            QualifiedExpressionPart::Integer(int)      => int.get_pos(),
-            QualifiedExpressionPart::StringLitteral(s) => s.get_pos(),
-            QualifiedExpressionPart::Char(c)           => c.get_pos(),
-            QualifiedExpressionPart::Boolean(b)        => b.get_pos(),
-            QualifiedExpressionPart::Closure(cl)       => cl.get_pos(),
-            QualifiedExpressionPart::ParenExpr(e)      => e.get_pos(),
-            QualifiedExpressionPart::Null              => unimplemented!(),
+            QualifiedExpressionPart::Identifier(i)     => i.get_span(),
+            QualifiedExpressionPart::MethodCall(f)     => f.get_span(),
+            QualifiedExpressionPart::Integer(int)      => int.get_span(),
+            QualifiedExpressionPart::Float(f)          => f.get_span(),
+            QualifiedExpressionPart::StringLitteral(s) => s.get_span(),
+            QualifiedExpressionPart::Char(c)           => c.get_span(),
+            QualifiedExpressionPart::Boolean(b)        => b.get_span(),
+            QualifiedExpressionPart::Closure(cl)       => cl.get_span(),
+            QualifiedExpressionPart::ParenExpr(e)      => e.get_span(),
+            QualifiedExpressionPart::Index(i)          => i.get_span(),
+            QualifiedExpressionPart::Array(a)          => a.get_span(),
+            QualifiedExpressionPart::Map(m)            => m.get_span(),
+            QualifiedExpressionPart::Null { span }     => *span,
         }
     }
 }
 
 impl<'a> FromPair<'a> for Expression {
-    fn from_pair<'b>(pair: Pair<'b, Rule>) -> Self {
+    fn from_pair<'b>(pair: Pair<'b, Rule>, file_id: FileId) -> Self {
         assert_eq!(pair.as_rule(), Rule::expression);
         
         let inner_iter: Pairs<'b, Rule> = pair.into_inner();
         PREC_CLIMBER.climb(
             inner_iter,
-            |pair: Pair<'b, Rule>| {
-                assert_eq!(pair.as_rule(), Rule::qualified_expression);
-                Expression::Expr(QualifiedExpression::from_pair(pair))
-            },
+            |pair: Pair<'b, Rule>| UnaryOperation::from_unary_expression(pair, file_id),
             |lval: Expression, op: Pair<'b, Rule>, rval: Expression| {
                 let operation = match op.as_rule() {
+                    Rule::or_op => OperationType::Or,
+                    Rule::and_op => OperationType::And,
+                    Rule::bor_op => OperationType::BitwiseOr,
+                    Rule::bxor_op => OperationType::BitwiseXor,
+                    Rule::band_op => OperationType::BitwiseAnd,
                     Rule::eq_op => OperationType::Eqal,
                     Rule::ne_op => OperationType::NotEqual,
                     Rule::gt_op => OperationType::GreaterThan,
                     Rule::ge_op => OperationType::GreaterOrEqual,
                     Rule::lt_op => OperationType::LowerThan,
                     Rule::le_op => OperationType::LowerOrEqual,
+                    Rule::shl_op => OperationType::ShiftLeft,
+                    Rule::shr_op => OperationType::ShiftRight,
                     Rule::ad_op => OperationType::Add,
                     Rule::mn_op => OperationType::Minus,
                     Rule::tm_op => OperationType::Times,
@@ -191,10 +351,11 @@ impl<'a> FromPair<'a> for Expression {
         ).clone()
     }
 
-    fn get_pos(&self) -> (usize, usize) {
+    fn get_span(&self) -> Span {
         match self {
-            Expression::Expr(e)      => e.get_pos(),
-            Expression::Operation(_) => unimplemented!()
+            Expression::Expr(e)      => e.get_span(),
+            Expression::Unary(u)     => u.get_span(),
+            Expression::Operation(o) => o.get_span()
         }
     }
 }
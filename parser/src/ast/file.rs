@@ -1,13 +1,16 @@
 use super::node::FromPair;
 use super::function::Function;
 use super::class::Class;
+use super::import::Import;
 use super::super::parser::Rule;
+use super::super::span::{Span, FileId};
 use pest::iterators::Pair;
 
 #[derive(Clone, Debug)]
 pub struct File {
+    span: Span,
     namespace: String,
-    imports: Vec<String>,
+    imports: Vec<Import>,
     entities: Vec<FirstClassEntity>,
 }
 
@@ -22,7 +25,7 @@ impl File {
         &self.namespace
     }
 
-    pub fn get_imports(&mut self) -> &mut Vec<String> {
+    pub fn get_imports(&mut self) -> &mut Vec<Import> {
         &mut self.imports
     }
 
@@ -30,8 +33,9 @@ impl File {
         &mut self.entities
     }
 
-    pub fn new(namespace: String, imports: Vec<String>, entities: Vec<FirstClassEntity>) -> Self {
+    pub fn new(span: Span, namespace: String, imports: Vec<Import>, entities: Vec<FirstClassEntity>) -> Self {
         File {
+            span,
             namespace,
             imports,
             entities,
@@ -40,9 +44,10 @@ impl File {
 }
 
 impl<'a> FromPair<'a> for File {
-    fn from_pair<'b>(pair: Pair<'b, Rule>) -> Self {
+    fn from_pair<'b>(pair: Pair<'b, Rule>, file_id: FileId) -> Self {
         assert_eq!(pair.as_rule(), Rule::file);
-        let mut imports: Vec<String> = vec![];
+        let span = Span::from_pair(&pair, file_id);
+        let mut imports: Vec<Import> = vec![];
         let mut namespace: String = String::new();
         let mut entities: Vec<FirstClassEntity> = vec![];
 
@@ -59,33 +64,27 @@ impl<'a> FromPair<'a> for File {
                     }
                 },
                 Rule::import => {
-                    for pair in pair.into_inner() {
-                        match pair.as_rule() {
-                            Rule::qualified_type => {
-                                imports.push(String::from(pair.as_str()));
-                            },
-                            _ => {}
-                        }
-                    }
+                    imports.push(Import::from_pair(pair, file_id));
                 },
                 Rule::method_decl => {
-                    entities.push(FirstClassEntity::Function(Function::from_pair(pair)));
+                    entities.push(FirstClassEntity::Function(Function::from_pair(pair, file_id)));
                 },
                 Rule::class_decl => {
-                    entities.push(FirstClassEntity::Class(Class::from_pair(pair)));
+                    entities.push(FirstClassEntity::Class(Class::from_pair(pair, file_id)));
                 },
                 _ => {}
             }
         }
 
         File {
+            span,
             namespace,
             imports,
             entities,
         }
     }
 
-    fn get_pos(&self) -> (usize, usize) {
-        (0, 0)
+    fn get_span(&self) -> Span {
+        self.span
     }
 }
@@ -3,23 +3,26 @@ use super::attribute::Attribute;
 use super::param::Param;
 use super::statement::Statement;
 use super::expression::Expression;
+use super::type_ref::{TypeRef, TypeParam};
 use super::super::parser::Rule;
+use super::super::span::{Span, FileId};
 use pest::iterators::Pair;
 
 #[derive(Clone, Debug)]
 pub struct Function {
-    pos: (usize, usize),
+    span: Span,
     attributes: Vec<Attribute>,
     name: String,
+    type_params: Vec<TypeParam>,
     params: Vec<Param>,
-    return_type: String,
+    return_type: TypeRef,
     has_body: bool,
     statements: Vec<Statement>,
 }
 
 #[derive(Clone, Debug)]
 pub struct FunctionCall {
-    pos: (usize, usize),
+    span: Span,
     name: String,
     param_exprs: Vec<Expression>,
 }
@@ -33,12 +36,16 @@ impl Function {
         &self.name
     }
 
+    pub fn get_type_params(&mut self) -> &mut Vec<TypeParam> {
+        &mut self.type_params
+    }
+
     pub fn get_params(&mut self) -> &mut Vec<Param> {
         &mut self.params
     }
 
-    pub fn get_return_type(&self) -> &str {
-        &self.return_type
+    pub fn get_return_type(&mut self) -> &mut TypeRef {
+        &mut self.return_type
     }
 
     pub fn get_statements(&mut self) ->&mut Vec<Statement> {
@@ -49,12 +56,14 @@ impl Function {
         self.has_body
     }
 
-    pub fn new(pos: (usize, usize), attributes: Vec<Attribute>, name: String, params: Vec<Param>,
-            return_type: String, has_body: bool, statements: Vec<Statement>) -> Self {
+    pub fn new(span: Span, attributes: Vec<Attribute>, name: String,
+            type_params: Vec<TypeParam>, params: Vec<Param>, return_type: TypeRef,
+            has_body: bool, statements: Vec<Statement>) -> Self {
         Function {
-            pos,
+            span,
             attributes,
             name,
+            type_params,
             params,
             return_type,
             has_body,
@@ -64,39 +73,43 @@ impl Function {
 }
 
 impl<'a> FromPair<'a> for Function {
-    fn from_pair<'b>(pair: Pair<'b, Rule>) -> Self {
+    fn from_pair<'b>(pair: Pair<'b, Rule>, file_id: FileId) -> Self {
         assert_eq!(pair.as_rule(), Rule::method_decl);
 
         let mut attributes: Vec<Attribute> = vec![];
         let mut name = String::new();
+        let mut type_params: Vec<TypeParam> = vec![];
         let mut params: Vec<Param> = vec![];
-        let mut return_type = String::new();
+        let mut return_type: Option<TypeRef> = None;
         let mut statements: Vec<Statement> = vec![];
-        let pos = pair.as_span().start_pos().line_col();
+        let span = Span::from_pair(&pair, file_id);
         let mut has_body = false;
 
         for pair in pair.into_inner() {
             match pair.as_rule() {
                 Rule::attribute_list => {
                     for attribute in pair.into_inner() {
-                        attributes.push(Attribute::from_pair(attribute));
+                        attributes.push(Attribute::from_pair(attribute, file_id));
                     }
                 },
                 Rule::ident | Rule::operator => {
                     name.insert_str(0, pair.as_str());
                 },
+                Rule::type_params => {
+                    type_params = TypeParam::from_type_params(pair, file_id);
+                },
                 Rule::param_list => {
                     for param in pair.into_inner() {
-                        params.push(Param::from_pair(param));
+                        params.push(Param::from_pair(param, file_id));
                     }
                 },
-                Rule::_type => {
-                    return_type.insert_str(0, pair.as_str());
+                Rule::type_ref => {
+                    return_type = Some(TypeRef::from_pair(pair, file_id));
                 },
                 Rule::executable_body => {
                     has_body = true;
                     for statement in pair.into_inner() {
-                        statements.push(Statement::from_pair(statement));
+                        statements.push(Statement::from_pair(statement, file_id));
                     }
                 },
                 _ => {}
@@ -104,18 +117,19 @@ impl<'a> FromPair<'a> for Function {
         }
 
         Function {
-            pos,
+            span,
             attributes,
             name,
+            type_params,
             params,
-            return_type,
+            return_type: return_type.unwrap(),
             has_body,
             statements,
         }
     }
 
-    fn get_pos(&self) -> (usize, usize) {
-        self.pos
+    fn get_span(&self) -> Span {
+        self.span
     }
 }
 
@@ -128,9 +142,9 @@ impl FunctionCall {
         &mut self.param_exprs
     }
 
-    pub fn new(pos: (usize, usize), name: String, param_exprs: Vec<Expression>) -> Self {
+    pub fn new(span: Span, name: String, param_exprs: Vec<Expression>) -> Self {
         FunctionCall {
-            pos,
+            span,
             name,
             param_exprs,
         }
@@ -138,10 +152,10 @@ impl FunctionCall {
 }
 
 impl<'a> FromPair<'a> for FunctionCall {
-    fn from_pair<'b>(pair: Pair<'b, Rule>) -> Self {
+    fn from_pair<'b>(pair: Pair<'b, Rule>, file_id: FileId) -> Self {
         assert_eq!(pair.as_rule(), Rule::method_call);
 
-        let pos = pair.as_span().start_pos().line_col();
+        let span = Span::from_pair(&pair, file_id);
         let mut inner_pair = pair.into_inner();
         let name = String::from(inner_pair.next().unwrap().as_str());
         let mut param_exprs: Vec<Expression> = vec![];
@@ -149,20 +163,20 @@ impl<'a> FromPair<'a> for FunctionCall {
         match inner_pair.next() {
             Some(pair) => {
                 for expr in pair.into_inner() {
-                    param_exprs.push(Expression::from_pair(expr));
+                    param_exprs.push(Expression::from_pair(expr, file_id));
                 }
             },
             None => {}
         }
 
         FunctionCall {
-            pos,
+            span,
             name,
             param_exprs,
         }
     }
 
-    fn get_pos(&self) -> (usize, usize) {
-        self.pos
+    fn get_span(&self) -> Span {
+        self.span
     }
 }
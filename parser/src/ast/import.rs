@@ -0,0 +1,135 @@
+use super::node::FromPair;
+use super::super::parser::Rule;
+use super::super::span::{Span, FileId};
+use pest::iterators::Pair;
+
+/**
+ * A `use` declaration, `path` being the qualified name written before the
+ * alias, the selection or the wildcard
+ */
+#[derive(Clone, Debug)]
+pub struct Import {
+    span: Span,
+    path: String,
+    kind: ImportKind,
+}
+
+#[derive(Clone, Debug)]
+pub enum ImportKind {
+    // `use A.B.C;` imports the namespace `A.B.C` or the class `C` of the
+    // namespace `A.B`, `use A.B.C as D;` imports the class under another name
+    Path(Option<String>),
+    // `use A.B.{C, D as E};` imports some classes of the namespace `A.B`
+    Selection(Vec<ImportedName>),
+    // `use A.B.*;` imports every class of the namespace `A.B`
+    Wildcard,
+}
+
+/**
+ * A name of an import selection with its optional alias
+ */
+#[derive(Clone, Debug)]
+pub struct ImportedName {
+    span: Span,
+    name: String,
+    alias: Option<String>,
+}
+
+impl Import {
+    pub fn get_path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn get_kind(&self) -> &ImportKind {
+        &self.kind
+    }
+
+    pub fn new(span: Span, path: String, kind: ImportKind) -> Self {
+        Import {
+            span,
+            path,
+            kind,
+        }
+    }
+}
+
+impl ImportedName {
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_alias(&self) -> Option<&str> {
+        self.alias.as_deref()
+    }
+
+    pub fn new(span: Span, name: String, alias: Option<String>) -> Self {
+        ImportedName {
+            span,
+            name,
+            alias,
+        }
+    }
+}
+
+/**
+ * Name given by an `import_alias` pair
+ */
+fn alias_from_pair(pair: Pair<Rule>) -> String {
+    assert_eq!(pair.as_rule(), Rule::import_alias);
+    String::from(pair.into_inner().next().unwrap().as_str())
+}
+
+impl<'a> FromPair<'a> for Import {
+    fn from_pair<'b>(pair: Pair<'b, Rule>, file_id: FileId) -> Self {
+        assert_eq!(pair.as_rule(), Rule::import);
+
+        let span = Span::from_pair(&pair, file_id);
+        let mut path = String::new();
+        let mut kind = ImportKind::Path(None);
+        for pair in pair.into_inner() {
+            match pair.as_rule() {
+                Rule::qualified_type => path = String::from(pair.as_str()),
+                Rule::import_alias => kind = ImportKind::Path(Some(alias_from_pair(pair))),
+                Rule::import_selection => {
+                    let names = pair.into_inner()
+                            .map(|pair| ImportedName::from_pair(pair, file_id))
+                            .collect();
+                    kind = ImportKind::Selection(names);
+                },
+                Rule::import_wildcard => kind = ImportKind::Wildcard,
+                _ => {}
+            }
+        }
+
+        Import {
+            span,
+            path,
+            kind,
+        }
+    }
+
+    fn get_span(&self) -> Span {
+        self.span
+    }
+}
+
+impl<'a> FromPair<'a> for ImportedName {
+    fn from_pair<'b>(pair: Pair<'b, Rule>, file_id: FileId) -> Self {
+        assert_eq!(pair.as_rule(), Rule::import_item);
+
+        let span = Span::from_pair(&pair, file_id);
+        let mut inner = pair.into_inner();
+        let name = String::from(inner.next().unwrap().as_str());
+        let alias = inner.next().map(alias_from_pair);
+
+        ImportedName {
+            span,
+            name,
+            alias,
+        }
+    }
+
+    fn get_span(&self) -> Span {
+        self.span
+    }
+}
@@ -1,10 +1,12 @@
 use super::node::FromPair;
+use super::expression::Expression;
 use super::super::parser::Rule;
+use super::super::span::{Span, FileId};
 use pest::iterators::Pair;
 
 #[derive(Clone, Debug)]
 pub struct Identifier {
-    pos: (usize, usize),
+    span: Span,
     name: String,
 }
 
@@ -13,104 +15,282 @@ impl Identifier {
         &self.name
     }
 
-    pub fn new(pos: (usize, usize), name: String) -> Self {
+    pub fn new(span: Span, name: String) -> Self {
         Identifier {
-            pos,
+            span,
             name,
         }
     }
 }
 
 impl<'a> FromPair<'a> for Identifier {
-    fn from_pair<'b>(pair: Pair<'b, Rule>) -> Self {
+    fn from_pair<'b>(pair: Pair<'b, Rule>, file_id: FileId) -> Self {
         assert_eq!(pair.as_rule(), Rule::ident);
 
         Identifier {
-            pos: pair.as_span().start_pos().line_col(),
+            span: Span::from_pair(&pair, file_id),
             name: String::from(pair.as_str()),
         }
     }
 
-    fn get_pos(&self) -> (usize, usize) {
-        self.pos
+    fn get_span(&self) -> Span {
+        self.span
     }
 }
 
 #[derive(Clone, Debug)]
 pub struct StringLitteral {
-    pos: (usize, usize),
+    span: Span,
     value: String,
 }
 
 impl StringLitteral {
+    /**
+     * The value with its escape sequences decoded
+     */
     pub fn get_value(&self) -> &str {
         &self.value
     }
 
-    pub fn new(pos: (usize, usize), value: String) -> Self {
+    pub fn new(span: Span, value: String) -> Self {
         StringLitteral {
-            pos,
+            span,
             value,
         }
     }
 }
 
 impl<'a> FromPair<'a> for StringLitteral {
-    fn from_pair<'b>(pair: Pair<'b, Rule>) -> Self {
+    fn from_pair<'b>(pair: Pair<'b, Rule>, file_id: FileId) -> Self {
         assert_eq!(pair.as_rule(), Rule::string);
 
-        let pos = pair.as_span().start_pos().line_col();
+        let span = Span::from_pair(&pair, file_id);
         let chars = pair.into_inner().next().unwrap();
-        assert_eq!(chars.as_rule(), Rule::string_chars);
-        
+        let value = match chars.as_rule() {
+            Rule::string_chars => unescape(chars.as_str()),
+            Rule::multiline_string_chars => {
+                let value = unescape(chars.as_str());
+                match value.strip_prefix("\r\n").or_else(|| value.strip_prefix('\n')) {
+                    Some(rest) => String::from(rest),
+                    None => value,
+                }
+            },
+            Rule::raw_string_chars => String::from(chars.as_str()),
+            _ => unreachable!()
+        };
+
         StringLitteral {
-            pos,
-            value: String::from(chars.as_str()),
+            span,
+            value,
         }
     }
 
-    fn get_pos(&self) -> (usize, usize) {
-        self.pos
+    fn get_span(&self) -> Span {
+        self.span
     }
 }
 
 #[derive(Clone, Debug)]
 pub struct Integer {
-    pos: (usize, usize),
-    value: i32,
+    span: Span,
+    value: u64,
+    int_type: IntegerType,
+    has_suffix: bool,
+}
+
+/**
+ * Width of an integer litteral, given by its suffix or else the smallest
+ * of Int and Int64 that can hold it
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum IntegerType {
+    Int8,
+    Int16,
+    Int,
+    Int64,
+    UInt8,
+    UInt16,
+    UInt,
+    UInt64,
+}
+
+impl IntegerType {
+    /**
+     * Name of the matching builtin type
+     */
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            IntegerType::Int8   => "Int8",
+            IntegerType::Int16  => "Int16",
+            IntegerType::Int    => "Int",
+            IntegerType::Int64  => "Int64",
+            IntegerType::UInt8  => "UInt8",
+            IntegerType::UInt16 => "UInt16",
+            IntegerType::UInt   => "UInt",
+            IntegerType::UInt64 => "UInt64",
+        }
+    }
+
+    pub fn get_max_value(&self) -> u64 {
+        match self {
+            IntegerType::Int8   => i8::MAX as u64,
+            IntegerType::Int16  => i16::MAX as u64,
+            IntegerType::Int    => i32::MAX as u64,
+            IntegerType::Int64  => i64::MAX as u64,
+            IntegerType::UInt8  => u8::MAX as u64,
+            IntegerType::UInt16 => u16::MAX as u64,
+            IntegerType::UInt   => u32::MAX as u64,
+            IntegerType::UInt64 => u64::MAX,
+        }
+    }
+
+    pub fn is_signed(&self) -> bool {
+        matches!(self, IntegerType::Int8 | IntegerType::Int16 | IntegerType::Int | IntegerType::Int64)
+    }
+
+    fn from_suffix(suffix: &str) -> Self {
+        match suffix {
+            "i8"  => IntegerType::Int8,
+            "i16" => IntegerType::Int16,
+            "i32" => IntegerType::Int,
+            "i64" => IntegerType::Int64,
+            "u8"  => IntegerType::UInt8,
+            "u16" => IntegerType::UInt16,
+            "u32" => IntegerType::UInt,
+            "u64" => IntegerType::UInt64,
+            _     => unreachable!()
+        }
+    }
 }
 
 impl Integer {
-    pub fn get_value(&self) -> &i32 {
+    /**
+     * Integer litterals are never negative, `-1` is a negation of `1`
+     */
+    pub fn get_value(&self) -> &u64 {
         &self.value
     }
 
-    pub fn new(pos: (usize, usize), value: i32) -> Self {
+    pub fn get_type(&self) -> &IntegerType {
+        &self.int_type
+    }
+
+    /**
+     * Without suffix the litteral can be given to any integer type that can
+     * hold its value
+     */
+    pub fn has_suffix(&self) -> bool {
+        self.has_suffix
+    }
+
+    pub fn new(span: Span, value: u64, int_type: IntegerType, has_suffix: bool) -> Self {
         Integer {
-            pos,
+            span,
             value,
+            int_type,
+            has_suffix,
         }
     }
 }
 
 impl<'a> FromPair<'a> for Integer {
-    fn from_pair<'b>(pair: Pair<'b, Rule>) -> Self {
+    fn from_pair<'b>(pair: Pair<'b, Rule>, file_id: FileId) -> Self {
         assert_eq!(pair.as_rule(), Rule::integer);
 
+        // the range was checked knowing whether the litteral is negated
+        // before building the ast
+        let (value, int_type) = parse_integer(pair.as_str(), true).unwrap();
+
         Integer {
-            pos: pair.as_span().start_pos().line_col(),
-            value: pair.as_str().parse::<i32>().unwrap(),
+            span: Span::from_pair(&pair, file_id),
+            value,
+            int_type,
+            has_suffix: pair.as_str().contains(['i', 'u']),
+        }
+    }
+
+    fn get_span(&self) -> Span {
+        self.span
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Float {
+    span: Span,
+    value: f64,
+    float_type: FloatType,
+    has_suffix: bool,
+}
+
+/**
+ * Float with the f32 suffix, Double otherwise
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FloatType {
+    Float,
+    Double,
+}
+
+impl FloatType {
+    /**
+     * Name of the matching builtin type
+     */
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            FloatType::Float  => "Float",
+            FloatType::Double => "Double",
         }
     }
+}
+
+impl Float {
+    pub fn get_value(&self) -> &f64 {
+        &self.value
+    }
 
-    fn get_pos(&self) -> (usize, usize) {
-        self.pos
+    pub fn get_type(&self) -> &FloatType {
+        &self.float_type
+    }
+
+    /**
+     * Without suffix the litteral can be given to Float as well as Double
+     */
+    pub fn has_suffix(&self) -> bool {
+        self.has_suffix
+    }
+
+    pub fn new(span: Span, value: f64, float_type: FloatType, has_suffix: bool) -> Self {
+        Float {
+            span,
+            value,
+            float_type,
+            has_suffix,
+        }
+    }
+}
+
+impl<'a> FromPair<'a> for Float {
+    fn from_pair<'b>(pair: Pair<'b, Rule>, file_id: FileId) -> Self {
+        assert_eq!(pair.as_rule(), Rule::float);
+
+        let (value, float_type) = parse_float(pair.as_str()).unwrap();
+
+        Float {
+            span: Span::from_pair(&pair, file_id),
+            value,
+            float_type,
+            has_suffix: pair.as_str().contains('f'),
+        }
+    }
+
+    fn get_span(&self) -> Span {
+        self.span
     }
 }
 
 #[derive(Clone, Debug)]
 pub struct Char {
-    pos: (usize, usize),
+    span: Span,
     value: char,
 }
 
@@ -119,32 +299,36 @@ impl Char {
         &self.value
     }
 
-    pub fn new(pos: (usize, usize), value: char) -> Self {
+    pub fn new(span: Span, value: char) -> Self {
         Char {
-            pos,
+            span,
             value,
         }
     }
 }
 
 impl<'a> FromPair<'a> for Char {
-    fn from_pair<'b>(pair: Pair<'b, Rule>) -> Self {
+    fn from_pair<'b>(pair: Pair<'b, Rule>, file_id: FileId) -> Self {
         assert_eq!(pair.as_rule(), Rule::character);
 
+        let text = pair.as_str();
+        // strips the quotes
+        let value = unescape(&text[1..text.len() - 1]).chars().next().unwrap();
+
         Char {
-            pos: pair.as_span().start_pos().line_col(),
-            value: char::from(pair.as_str().as_bytes()[1]),
+            span: Span::from_pair(&pair, file_id),
+            value,
         }
     }
 
-    fn get_pos(&self) -> (usize, usize) {
-        self.pos
+    fn get_span(&self) -> Span {
+        self.span
     }
 }
 
 #[derive(Clone, Debug)]
 pub struct Boolean {
-    pos: (usize, usize),
+    span: Span,
     value: bool,
 }
 
@@ -153,25 +337,210 @@ impl Boolean {
         &self.value
     }
 
-    pub fn new(pos: (usize, usize), value: bool) -> Self {
+    pub fn new(span: Span, value: bool) -> Self {
         Boolean {
-            pos,
+            span,
             value,
         }
     }
 }
 
 impl<'a> FromPair<'a> for Boolean {
-    fn from_pair<'b>(pair: Pair<'b, Rule>) -> Self {
+    fn from_pair<'b>(pair: Pair<'b, Rule>, file_id: FileId) -> Self {
         assert_eq!(pair.as_rule(), Rule::boolean);
 
         Boolean {
-            pos: pair.as_span().start_pos().line_col(),
+            span: Span::from_pair(&pair, file_id),
             value: pair.as_str().parse::<bool>().unwrap(),
         }
     }
 
-    fn get_pos(&self) -> (usize, usize) {
-        self.pos
+    fn get_span(&self) -> Span {
+        self.span
     }
 }
+
+#[derive(Clone, Debug)]
+pub struct ArrayLitteral {
+    span: Span,
+    items: Vec<Expression>,
+}
+
+impl ArrayLitteral {
+    pub fn get_items(&mut self) -> &mut Vec<Expression> {
+        &mut self.items
+    }
+
+    pub fn new(span: Span, items: Vec<Expression>) -> Self {
+        ArrayLitteral {
+            span,
+            items,
+        }
+    }
+}
+
+impl<'a> FromPair<'a> for ArrayLitteral {
+    fn from_pair<'b>(pair: Pair<'b, Rule>, file_id: FileId) -> Self {
+        assert_eq!(pair.as_rule(), Rule::array);
+
+        let span = Span::from_pair(&pair, file_id);
+        let mut items: Vec<Expression> = vec![];
+        for item in pair.into_inner() {
+            items.push(Expression::from_pair(item, file_id));
+        }
+
+        ArrayLitteral {
+            span,
+            items,
+        }
+    }
+
+    fn get_span(&self) -> Span {
+        self.span
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MapLitteral {
+    span: Span,
+    entries: Vec<(Expression, Expression)>,
+}
+
+impl MapLitteral {
+    /**
+     * (key, value) pairs in the order they are written
+     */
+    pub fn get_entries(&mut self) -> &mut Vec<(Expression, Expression)> {
+        &mut self.entries
+    }
+
+    pub fn new(span: Span, entries: Vec<(Expression, Expression)>) -> Self {
+        MapLitteral {
+            span,
+            entries,
+        }
+    }
+}
+
+impl<'a> FromPair<'a> for MapLitteral {
+    fn from_pair<'b>(pair: Pair<'b, Rule>, file_id: FileId) -> Self {
+        assert_eq!(pair.as_rule(), Rule::map);
+
+        let span = Span::from_pair(&pair, file_id);
+        let mut entries: Vec<(Expression, Expression)> = vec![];
+        for entry in pair.into_inner() {
+            let mut inner_iter = entry.into_inner();
+            let key = Expression::from_pair(inner_iter.next().unwrap(), file_id);
+            let value = Expression::from_pair(inner_iter.next().unwrap(), file_id);
+            entries.push((key, value));
+        }
+
+        MapLitteral {
+            span,
+            entries,
+        }
+    }
+
+    fn get_span(&self) -> Span {
+        self.span
+    }
+}
+
+/**
+ * Decodes a single escape sequence, None when a `\u{...}` escape is not a
+ * valid unicode scalar value
+ */
+pub(crate) fn decode_escape(escape: &str) -> Option<char> {
+    match escape {
+        "\\n" => Some('\n'),
+        "\\t" => Some('\t'),
+        "\\r" => Some('\r'),
+        "\\0" => Some('\0'),
+        "\\\\" => Some('\\'),
+        "\\\"" => Some('"'),
+        "\\'" => Some('\''),
+        _ => {
+            // \u{...}
+            let hex = escape.trim_start_matches("\\u{").trim_end_matches('}');
+            u32::from_str_radix(hex, 16).ok().and_then(char::from_u32)
+        }
+    }
+}
+
+/**
+ * Decodes the escape sequences of a string or character litteral, the
+ * escapes are known to be valid once the file has been parsed
+ */
+fn unescape(text: &str) -> String {
+    let mut value = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(i) = rest.find('\\') {
+        value.push_str(&rest[..i]);
+        let escape_len = if rest[i..].starts_with("\\u") {
+            rest[i..].find('}').unwrap() + 1
+        } else {
+            2
+        };
+        value.push(decode_escape(&rest[i..i + escape_len]).unwrap());
+        rest = &rest[i + escape_len..];
+    }
+    value.push_str(rest);
+
+    value
+}
+
+/**
+ * Reads an integer litteral, fails when it does not fit in its type. A
+ * `negated` litteral of a signed type can go up to the opposite of the type
+ * minimum: `-128i8`
+ */
+pub(crate) fn parse_integer(text: &str, negated: bool) -> Result<(u64, IntegerType), String> {
+    let digits = text.replace('_', "");
+    // hexadecimal digits never contain `i` or `u`
+    let (digits, suffix) = match digits.find(['i', 'u']) {
+        Some(i) => (&digits[..i], Some(&digits[i..])),
+        None => (&digits[..], None),
+    };
+    let (radix, digits) = match digits.get(..2) {
+        Some("0x") => (16, &digits[2..]),
+        Some("0b") => (2, &digits[2..]),
+        Some("0o") => (8, &digits[2..]),
+        _ => (10, digits),
+    };
+    let too_large = |type_name: &str| format!("integer litteral `{}` is too large for {}", text, type_name);
+
+    let value = u64::from_str_radix(digits, radix).map_err(|_| too_large("UInt64"))?;
+    let int_type = match suffix {
+        Some(suffix) => IntegerType::from_suffix(suffix),
+        None if value <= IntegerType::Int.get_max_value() => IntegerType::Int,
+        None => IntegerType::Int64,
+    };
+    let limit = if negated && int_type.is_signed() { int_type.get_max_value() + 1 } else { int_type.get_max_value() };
+    if value > limit {
+        return Err(too_large(int_type.get_name()));
+    }
+
+    Ok((value, int_type))
+}
+
+/**
+ * Reads a float litteral, fails when it is too large for its type
+ */
+pub(crate) fn parse_float(text: &str) -> Result<(f64, FloatType), String> {
+    let digits = text.replace('_', "");
+    let (digits, float_type) = if digits.ends_with("f32") {
+        (&digits[..digits.len() - 3], FloatType::Float)
+    } else {
+        (digits.trim_end_matches("f64"), FloatType::Double)
+    };
+    let value: f64 = digits.parse().unwrap();
+    let is_infinite = match float_type {
+        FloatType::Float  => (value as f32).is_infinite(),
+        FloatType::Double => value.is_infinite(),
+    };
+    if is_infinite {
+        return Err(format!("float litteral `{}` is too large for {}", text, float_type.get_name()));
+    }
+
+    Ok((value, float_type))
+}
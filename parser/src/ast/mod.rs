@@ -1,25 +1,39 @@
 mod file;
+mod import;
 mod node;
 mod attribute;
 mod function;
 mod param;
 mod expression;
 mod statement;
+mod control_flow;
 mod closure;
 mod variable;
 mod class;
 mod litterals;
+mod type_ref;
 
 pub use self::{
     file::{File, FirstClassEntity},
+    import::{Import, ImportKind, ImportedName},
     node::{FromPair, AstNode, ToAny, AstNodeType},
     attribute::Attribute,
     function::{Function, FunctionCall},
     param::Param,
-    expression::{Operation, Expression, QualifiedExpression, QualifiedExpressionPart},
+    expression::{
+        Operation, OperationType, UnaryOperation, UnaryOperationType, Expression,
+        QualifiedExpression, QualifiedExpressionPart, Index
+    },
     statement::Statement,
+    control_flow::{IfStatement, WhileStatement, ForStatement},
     closure::Closure,
     variable::{VariableDeclaration, VariableAffectation},
     class::{Class, ClassMember, Field, Block},
-    litterals::{Identifier, StringLitteral, Integer, Char, Boolean}
+    litterals::{
+        Identifier, StringLitteral, Integer, IntegerType, Float, FloatType, Char, Boolean,
+        ArrayLitteral, MapLitteral
+    },
+    type_ref::{TypeRef, NamedType, ClosureType, TypeParam}
 };
+
+pub(crate) use self::litterals::{decode_escape, parse_integer, parse_float};
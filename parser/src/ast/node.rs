@@ -1,10 +1,11 @@
 use pest::iterators::Pair;
 use super::super::parser::Rule;
+use super::super::span::{Span, FileId};
 use std::any::Any;
 
 pub trait FromPair<'a> {
-    fn from_pair<'b>(pair: Pair<'b, Rule>) -> Self;
-    fn get_pos(&self) -> (usize, usize);
+    fn from_pair<'b>(pair: Pair<'b, Rule>, file_id: FileId) -> Self;
+    fn get_span(&self) -> Span;
 }
 
 /********** unused *****************/
@@ -1,47 +1,49 @@
 use super::node::FromPair;
+use super::type_ref::TypeRef;
 use super::super::parser::Rule;
+use super::super::span::{Span, FileId};
 use pest::iterators::Pair;
 
 #[derive(Clone, Debug)]
 pub struct Param {
-    pos: (usize, usize),
+    span: Span,
     name: String,
-    type_name: String,
+    param_type: TypeRef,
 }
 
 impl Param {
-    pub fn get_type(&self) -> &str {
-        &self.type_name
+    pub fn get_type(&mut self) -> &mut TypeRef {
+        &mut self.param_type
     }
 
     pub fn get_name(&self) -> &str {
         &self.name
     }
 
-    pub fn new(pos: (usize, usize), name: String, type_name: String) -> Self {
+    pub fn new(span: Span, name: String, param_type: TypeRef) -> Self {
         Param {
-            pos,
+            span,
             name,
-            type_name,
+            param_type,
         }
     }
 }
 
 impl<'a> FromPair<'a> for Param {
-    fn from_pair<'b>(pair: Pair<'b, Rule>) -> Self {
+    fn from_pair<'b>(pair: Pair<'b, Rule>, file_id: FileId) -> Self {
         assert_eq!(pair.as_rule(), Rule::ident_type_pair);
 
-        let pos = pair.as_span().start_pos().line_col();
+        let span = Span::from_pair(&pair, file_id);
         let mut inner_iter = pair.into_inner();
 
         Param {
-            pos,
+            span,
             name: String::from(inner_iter.next().unwrap().as_str()),
-            type_name: String::from(inner_iter.next().unwrap().as_str())
+            param_type: TypeRef::from_pair(inner_iter.next().unwrap(), file_id)
         }
     }
 
-    fn get_pos(&self) -> (usize, usize) {
-        self.pos
+    fn get_span(&self) -> Span {
+        self.span
     }
 }
@@ -1,49 +1,76 @@
 use super::expression::{Expression, QualifiedExpression};
 use super::node::FromPair;
 use super::variable::{VariableDeclaration, VariableAffectation};
+use super::control_flow::{IfStatement, WhileStatement, ForStatement};
 use super::super::parser::Rule;
+use super::super::span::{Span, FileId};
 use pest::iterators::Pair;
 
 #[derive(Clone, Debug)]
 pub enum Statement {
-    ReturnStatement { expr: Option<Expression>, pos: (usize, usize) },
+    ReturnStatement { expr: Option<Expression>, span: Span },
     Declaration(VariableDeclaration),
     Affectation(VariableAffectation),
     QualifiedExpression(QualifiedExpression),
+    If(IfStatement),
+    While(WhileStatement),
+    For(ForStatement),
+    Break { span: Span },
+    Continue { span: Span },
 }
 
 impl<'a> FromPair<'a> for Statement {
-    fn from_pair<'b>(pair: Pair<'b, Rule>) -> Self {
+    fn from_pair<'b>(pair: Pair<'b, Rule>, file_id: FileId) -> Self {
         assert_eq!(pair.as_rule(), Rule::statement);
 
         let inner_pair = pair.into_inner().next().unwrap();
         match inner_pair.as_rule() {
             Rule::return_stmt => {
-                let pos = inner_pair.as_span().start_pos().line_col();
+                let span = Span::from_pair(&inner_pair, file_id);
                 match inner_pair.into_inner().next() {
-                    Some(e) => Statement::ReturnStatement { expr: Some(Expression::from_pair(e)), pos },
-                    None => Statement::ReturnStatement { expr: None, pos }
+                    Some(e) => Statement::ReturnStatement { expr: Some(Expression::from_pair(e, file_id)), span },
+                    None => Statement::ReturnStatement { expr: None, span }
                 }
             },
             Rule::declaration => {
-                Statement::Declaration(VariableDeclaration::from_pair(inner_pair))
+                Statement::Declaration(VariableDeclaration::from_pair(inner_pair, file_id))
             },
             Rule::affectation => {
-                Statement::Affectation(VariableAffectation::from_pair(inner_pair))
+                Statement::Affectation(VariableAffectation::from_pair(inner_pair, file_id))
             },
             Rule::qualified_expression => {
-                Statement::QualifiedExpression(QualifiedExpression::from_pair(inner_pair))
+                Statement::QualifiedExpression(QualifiedExpression::from_pair(inner_pair, file_id))
+            },
+            Rule::if_stmt => {
+                Statement::If(IfStatement::from_pair(inner_pair, file_id))
+            },
+            Rule::while_stmt => {
+                Statement::While(WhileStatement::from_pair(inner_pair, file_id))
+            },
+            Rule::for_stmt => {
+                Statement::For(ForStatement::from_pair(inner_pair, file_id))
+            },
+            Rule::break_stmt => {
+                Statement::Break { span: Span::from_pair(&inner_pair, file_id) }
+            },
+            Rule::continue_stmt => {
+                Statement::Continue { span: Span::from_pair(&inner_pair, file_id) }
             },
             _ => unreachable!()
         }
     }
 
-    fn get_pos(&self) -> (usize, usize) {
+    fn get_span(&self) -> Span {
         match self {
-            Statement::ReturnStatement { expr: _, pos } => pos.clone(),
-            Statement::Declaration(d)         => d.get_pos(),
-            Statement::Affectation(a)         => a.get_pos(),
-            Statement::QualifiedExpression(e) => e.get_pos(),
+            Statement::ReturnStatement { expr: _, span } => *span,
+            Statement::Declaration(d)         => d.get_span(),
+            Statement::Affectation(a)         => a.get_span(),
+            Statement::QualifiedExpression(e) => e.get_span(),
+            Statement::If(i)                  => i.get_span(),
+            Statement::While(w)               => w.get_span(),
+            Statement::For(f)                 => f.get_span(),
+            Statement::Break { span }         => *span,
+            Statement::Continue { span }      => *span,
         }
     }
 }
@@ -0,0 +1,264 @@
+use super::node::FromPair;
+use super::super::parser::Rule;
+use super::super::span::{Span, FileId};
+use pest::iterators::Pair;
+use std::fmt;
+
+/**
+ * A type as written in the source: `Int`, `List<T>`, `Map<String, List<Int>>`,
+ * `Closure|Int, Int|: Bool`
+ */
+#[derive(Clone, Debug)]
+pub enum TypeRef {
+    Named(NamedType),
+    Closure(ClosureType),
+}
+
+#[derive(Clone, Debug)]
+pub struct NamedType {
+    span: Span,
+    name: String,
+    type_args: Vec<TypeRef>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ClosureType {
+    span: Span,
+    params: Vec<TypeRef>,
+    return_type: Box<TypeRef>,
+}
+
+/**
+ * A type parameter declared by a generic class or method, with its
+ * optional bounds: `T`, `T: Comparable<T>`, `T: Hashable + Printable`
+ */
+#[derive(Clone, Debug)]
+pub struct TypeParam {
+    span: Span,
+    name: String,
+    bounds: Vec<TypeRef>,
+}
+
+impl TypeRef {
+    /**
+     * Compares two types without taking their positions into account
+     */
+    pub fn is_same_type(&self, other: &TypeRef) -> bool {
+        match (self, other) {
+            (TypeRef::Named(a), TypeRef::Named(b)) => {
+                a.name == b.name && a.type_args.len() == b.type_args.len()
+                    && a.type_args.iter().zip(b.type_args.iter()).all(|(a, b)| a.is_same_type(b))
+            },
+            (TypeRef::Closure(a), TypeRef::Closure(b)) => {
+                a.return_type.is_same_type(&b.return_type) && a.params.len() == b.params.len()
+                    && a.params.iter().zip(b.params.iter()).all(|(a, b)| a.is_same_type(b))
+            },
+            _ => false,
+        }
+    }
+}
+
+impl<'a> FromPair<'a> for TypeRef {
+    fn from_pair<'b>(pair: Pair<'b, Rule>, file_id: FileId) -> Self {
+        assert_eq!(pair.as_rule(), Rule::type_ref);
+
+        let inner_pair = pair.into_inner().next().unwrap();
+        match inner_pair.as_rule() {
+            Rule::named_type   => TypeRef::Named(NamedType::from_pair(inner_pair, file_id)),
+            Rule::closure_type => TypeRef::Closure(ClosureType::from_pair(inner_pair, file_id)),
+            _                  => unreachable!()
+        }
+    }
+
+    fn get_span(&self) -> Span {
+        match self {
+            TypeRef::Named(n)   => n.get_span(),
+            TypeRef::Closure(c) => c.get_span(),
+        }
+    }
+}
+
+impl fmt::Display for TypeRef {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TypeRef::Named(n)   => n.fmt(f),
+            TypeRef::Closure(c) => c.fmt(f),
+        }
+    }
+}
+
+impl NamedType {
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_type_args(&mut self) -> &mut Vec<TypeRef> {
+        &mut self.type_args
+    }
+
+    pub fn new(span: Span, name: String, type_args: Vec<TypeRef>) -> Self {
+        NamedType {
+            span,
+            name,
+            type_args,
+        }
+    }
+}
+
+impl<'a> FromPair<'a> for NamedType {
+    fn from_pair<'b>(pair: Pair<'b, Rule>, file_id: FileId) -> Self {
+        assert_eq!(pair.as_rule(), Rule::named_type);
+
+        let span = Span::from_pair(&pair, file_id);
+        let mut inner_iter = pair.into_inner();
+        let name_pair = inner_iter.next().unwrap();
+        // the whitespace skipped while looking for type args is not part
+        // of the type
+        let mut end_byte = name_pair.as_span().end();
+        let name = String::from(name_pair.as_str());
+        let mut type_args: Vec<TypeRef> = vec![];
+        if let Some(args) = inner_iter.next() {
+            end_byte = args.as_span().end();
+            for arg in args.into_inner() {
+                type_args.push(TypeRef::from_pair(arg, file_id));
+            }
+        }
+
+        NamedType {
+            span: Span::new(file_id, span.get_start_byte(), end_byte),
+            name,
+            type_args,
+        }
+    }
+
+    fn get_span(&self) -> Span {
+        self.span
+    }
+}
+
+impl fmt::Display for NamedType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        if !self.type_args.is_empty() {
+            let args: Vec<String> = self.type_args.iter().map(|arg| arg.to_string()).collect();
+            write!(f, "<{}>", args.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl ClosureType {
+    pub fn get_params(&mut self) -> &mut Vec<TypeRef> {
+        &mut self.params
+    }
+
+    pub fn get_return_type(&mut self) -> &mut TypeRef {
+        &mut self.return_type
+    }
+
+    pub fn new(span: Span, params: Vec<TypeRef>, return_type: TypeRef) -> Self {
+        ClosureType {
+            span,
+            params,
+            return_type: Box::new(return_type),
+        }
+    }
+}
+
+impl<'a> FromPair<'a> for ClosureType {
+    fn from_pair<'b>(pair: Pair<'b, Rule>, file_id: FileId) -> Self {
+        assert_eq!(pair.as_rule(), Rule::closure_type);
+
+        let span = Span::from_pair(&pair, file_id);
+        let mut types: Vec<TypeRef> = vec![];
+        for pair in pair.into_inner() {
+            types.push(TypeRef::from_pair(pair, file_id));
+        }
+        // the return type is always the last one
+        let return_type = types.pop().unwrap();
+
+        ClosureType {
+            span,
+            params: types,
+            return_type: Box::new(return_type),
+        }
+    }
+
+    fn get_span(&self) -> Span {
+        self.span
+    }
+}
+
+impl fmt::Display for ClosureType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let params: Vec<String> = self.params.iter().map(|param| param.to_string()).collect();
+        write!(f, "Closure|{}|: {}", params.join(", "), self.return_type)
+    }
+}
+
+impl TypeParam {
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_bounds(&mut self) -> &mut Vec<TypeRef> {
+        &mut self.bounds
+    }
+
+    pub fn new(span: Span, name: String, bounds: Vec<TypeRef>) -> Self {
+        TypeParam {
+            span,
+            name,
+            bounds,
+        }
+    }
+
+    /**
+     * Reads the params of a type_params rule
+     */
+    pub fn from_type_params(pair: Pair<Rule>, file_id: FileId) -> Vec<TypeParam> {
+        assert_eq!(pair.as_rule(), Rule::type_params);
+
+        let mut type_params: Vec<TypeParam> = vec![];
+        for param in pair.into_inner() {
+            type_params.push(TypeParam::from_pair(param, file_id));
+        }
+
+        type_params
+    }
+}
+
+impl<'a> FromPair<'a> for TypeParam {
+    fn from_pair<'b>(pair: Pair<'b, Rule>, file_id: FileId) -> Self {
+        assert_eq!(pair.as_rule(), Rule::type_param);
+
+        let span = Span::from_pair(&pair, file_id);
+        let mut inner_iter = pair.into_inner();
+        let name = String::from(inner_iter.next().unwrap().as_str());
+        let mut bounds: Vec<TypeRef> = vec![];
+        for bound in inner_iter {
+            bounds.push(TypeRef::from_pair(bound, file_id));
+        }
+
+        TypeParam {
+            span,
+            name,
+            bounds,
+        }
+    }
+
+    fn get_span(&self) -> Span {
+        self.span
+    }
+}
+
+impl fmt::Display for TypeParam {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        if !self.bounds.is_empty() {
+            let bounds: Vec<String> = self.bounds.iter().map(|bound| bound.to_string()).collect();
+            write!(f, ": {}", bounds.join(" + "))?;
+        }
+        Ok(())
+    }
+}
@@ -1,19 +1,21 @@
 use super::node::FromPair;
 use super::expression::{QualifiedExpression, Expression};
+use super::type_ref::TypeRef;
 use super::super::parser::Rule;
+use super::super::span::{Span, FileId};
 use pest::iterators::Pair;
 
 #[derive(Clone, Debug)]
 pub struct VariableDeclaration {
-    pos: (usize, usize),
+    span: Span,
     name: String,
-    type_name: String,
+    var_type: TypeRef,
     value: Option<Expression>,
 }
 
 #[derive(Clone, Debug)]
 pub struct VariableAffectation {
-    pos: (usize, usize),
+    span: Span,
     receiver: QualifiedExpression,
     value: Expression,
 }
@@ -23,47 +25,47 @@ impl VariableDeclaration {
         &self.name
     }
 
-    pub fn get_type_name(&self) -> &str {
-        &self.type_name
+    pub fn get_type(&mut self) -> &mut TypeRef {
+        &mut self.var_type
     }
 
     pub fn get_value(&mut self) -> &mut Option<Expression> {
         &mut self.value
     }
 
-    pub fn new(pos: (usize, usize), name: String, type_name: String, value: Option<Expression>) -> Self {
+    pub fn new(span: Span, name: String, var_type: TypeRef, value: Option<Expression>) -> Self {
         VariableDeclaration {
-            pos,
+            span,
             name,
-            type_name,
+            var_type,
             value,
         }
     }
 }
 
 impl<'a> FromPair<'a> for VariableDeclaration {
-    fn from_pair<'b>(pair: Pair<'b, Rule>) -> Self {
+    fn from_pair<'b>(pair: Pair<'b, Rule>, file_id: FileId) -> Self {
         assert_eq!(pair.as_rule(), Rule::declaration);
 
-        let pos = pair.as_span().start_pos().line_col();
+        let span = Span::from_pair(&pair, file_id);
         let mut inner_pair = pair.into_inner();
         let name = String::from(inner_pair.next().unwrap().as_str());
-        let type_name = String::from(inner_pair.next().unwrap().as_str());
+        let var_type = TypeRef::from_pair(inner_pair.next().unwrap(), file_id);
         let value = match inner_pair.next() {
-            Some(expr_pair) => Some(Expression::from_pair(expr_pair)),
+            Some(expr_pair) => Some(Expression::from_pair(expr_pair, file_id)),
             None => None
         };
 
         VariableDeclaration {
-            pos,
+            span,
             name,
-            type_name,
+            var_type,
             value
         }
     }
 
-    fn get_pos(&self) -> (usize, usize) {
-        self.pos
+    fn get_span(&self) -> Span {
+        self.span
     }
 }
 
@@ -76,9 +78,9 @@ impl VariableAffectation {
         &mut self.value
     }
 
-    pub fn new(pos: (usize, usize), receiver: QualifiedExpression, value: Expression) -> Self {
+    pub fn new(span: Span, receiver: QualifiedExpression, value: Expression) -> Self {
         VariableAffectation {
-            pos,
+            span,
             receiver,
             value,
         }
@@ -86,20 +88,20 @@ impl VariableAffectation {
 }
 
 impl<'a> FromPair<'a> for VariableAffectation {
-    fn from_pair<'b>(pair: Pair<'b, Rule>) -> Self {
+    fn from_pair<'b>(pair: Pair<'b, Rule>, file_id: FileId) -> Self {
         assert_eq!(pair.as_rule(), Rule::affectation);
 
-        let pos = pair.as_span().start_pos().line_col();
+        let span = Span::from_pair(&pair, file_id);
         let mut inner_iter = pair.into_inner();
 
         VariableAffectation {
-            pos,
-            receiver: QualifiedExpression::from_pair(inner_iter.next().unwrap()),
-            value: Expression::from_pair(inner_iter.next().unwrap())
+            span,
+            receiver: QualifiedExpression::from_pair(inner_iter.next().unwrap(), file_id),
+            value: Expression::from_pair(inner_iter.next().unwrap(), file_id)
         }
     }
 
-    fn get_pos(&self) -> (usize, usize) {
-        self.pos
+    fn get_span(&self) -> Span {
+        self.span
     }
 }
@@ -0,0 +1,213 @@
+use super::parser::Rule;
+use super::span::{Span, FileId};
+use pest::error::{Error, ErrorVariant, InputLocation};
+use std::fmt;
+
+/**
+ * A syntax error found while parsing a source file.
+ * Positions are (line, column), both starting at 1.
+ */
+#[derive(Clone, Debug)]
+pub struct ParseDiagnostic {
+    span: Span,
+    file: String,
+    start: (usize, usize),
+    end: (usize, usize),
+    message: String,
+}
+
+impl ParseDiagnostic {
+    pub fn get_span(&self) -> Span {
+        self.span
+    }
+
+    pub fn get_file(&self) -> &str {
+        &self.file
+    }
+
+    pub fn get_start(&self) -> (usize, usize) {
+        self.start
+    }
+
+    pub fn get_end(&self) -> (usize, usize) {
+        self.end
+    }
+
+    pub fn get_message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn new(span: Span, file: String, start: (usize, usize), end: (usize, usize), message: String) -> Self {
+        ParseDiagnostic {
+            span,
+            file,
+            start,
+            end,
+            message,
+        }
+    }
+
+    /**
+     * Builds a diagnostic for the text between two byte offsets of `code`
+     */
+    pub fn from_span(file_id: FileId, file: &str, code: &str, start: usize, end: usize, message: String) -> Self {
+        ParseDiagnostic {
+            span: Span::new(file_id, start, end),
+            file: String::from(file),
+            start: line_col(code, start),
+            end: line_col(code, end),
+            message,
+        }
+    }
+
+    /**
+     * Builds a diagnostic from a pest error, positions are computed on
+     * `code` so that they stay right even if the parsed text was altered
+     * during error recovery
+     */
+    pub fn from_error(file_id: FileId, file: &str, code: &str, error: &Error<Rule>) -> Self {
+        let (start_byte, end_byte) = match error.location {
+            InputLocation::Pos(pos) => (pos, token_end(code, pos)),
+            InputLocation::Span((start, end)) => (start, end),
+        };
+        let mut expected: Vec<String> = vec![];
+        let message = match &error.variant {
+            ErrorVariant::ParsingError { positives, negatives } => {
+                for rule in positives {
+                    let description = describe_rule(rule);
+                    if !expected.contains(&description) {
+                        expected.push(description);
+                    }
+                }
+                let mut unexpected: Vec<String> = vec![];
+                for rule in negatives {
+                    let description = describe_rule(rule);
+                    if !unexpected.contains(&description) {
+                        unexpected.push(description);
+                    }
+                }
+                match (expected.is_empty(), unexpected.is_empty()) {
+                    (false, true) => format!("expected {}", enumerate(&expected)),
+                    (true, false) => format!("unexpected {}", enumerate(&unexpected)),
+                    (false, false) => format!("unexpected {}, expected {}",
+                            enumerate(&unexpected), enumerate(&expected)),
+                    (true, true) => String::from("unexpected input"),
+                }
+            },
+            ErrorVariant::CustomError { message } => message.clone(),
+        };
+
+        ParseDiagnostic {
+            span: Span::new(file_id, start_byte, end_byte),
+            file: String::from(file),
+            start: line_col(code, start_byte),
+            end: line_col(code, end_byte),
+            message,
+        }
+    }
+}
+
+impl fmt::Display for ParseDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (line, col) = self.start;
+        write!(f, "{}: {},{}: {}", self.file, line, col, self.message)
+    }
+}
+
+/**
+ * Plain words used in messages for each grammar rule
+ */
+fn describe_rule(rule: &Rule) -> String {
+    let description = match rule {
+        Rule::namespace_end => "`;` after namespace declaration",
+        Rule::import_end => "`;` after `use` declaration",
+        Rule::field_end => "`;` after field declaration",
+        Rule::method_end => "`;` or a body after method declaration",
+        Rule::stmt_end => "`;` after statement",
+        Rule::namespace => "namespace declaration",
+        Rule::import => "`use` declaration",
+        Rule::import_alias => "`as` alias",
+        Rule::import_selection | Rule::import_item => "imported name",
+        Rule::import_wildcard => "`*`",
+        Rule::class_decl => "class declaration",
+        Rule::method_decl => "function or method declaration",
+        Rule::field_decl => "field declaration",
+        Rule::block_decl => "attributes block",
+        Rule::extended_class => "superclass name",
+        Rule::interface_list => "interface name",
+        Rule::attribute | Rule::attribute_list => "attribute",
+        Rule::ident | Rule::qualified_ident => "identifier",
+        Rule::_type | Rule::qualified_type | Rule::type_ref | Rule::named_type => "type name",
+        Rule::closure_type => "closure type",
+        Rule::type_args => "type arguments",
+        Rule::type_params => "type parameters",
+        Rule::type_param => "type parameter",
+        Rule::param_list | Rule::ident_type_pair => "parameter",
+        Rule::executable_body => "`{`",
+        Rule::statement => "statement",
+        Rule::return_stmt => "`return` statement",
+        Rule::declaration => "variable declaration",
+        Rule::affectation => "assignment",
+        Rule::expression | Rule::unary_expression | Rule::qualified_expression => "expression",
+        Rule::method_call => "method call",
+        Rule::method_call_params => "argument",
+        Rule::operator => "operator declaration",
+        Rule::index_op => "`[]`",
+        Rule::index_set_op => "`[]=`",
+        Rule::index => "index",
+        Rule::array => "array",
+        Rule::map | Rule::map_entry => "map",
+        Rule::or_op | Rule::and_op | Rule::bor_op | Rule::bxor_op | Rule::band_op
+            | Rule::eq_op | Rule::ne_op | Rule::shl_op | Rule::shr_op | Rule::gt_op
+            | Rule::ge_op | Rule::lt_op | Rule::le_op | Rule::ad_op | Rule::mn_op
+            | Rule::tm_op | Rule::dv_op | Rule::md_op
+            | Rule::not_op | Rule::neg_op | Rule::bnot_op => "operator",
+        Rule::integer | Rule::hex_digits | Rule::bin_digits | Rule::oct_digits
+            | Rule::dec_digits | Rule::integer_suffix => "integer",
+        Rule::float | Rule::float_suffix => "number",
+        Rule::character => "character",
+        Rule::boolean => "boolean",
+        Rule::null => "`null`",
+        Rule::string | Rule::string_chars | Rule::multiline_string_chars
+            | Rule::raw_string_chars => "string",
+        Rule::escape => "escape sequence",
+        Rule::closure => "closure",
+        Rule::EOI => "end of file",
+        _ => return format!("{:?}", rule),
+    };
+    String::from(description)
+}
+
+/**
+ * ["a"] -> "a", ["a", "b", "c"] -> "a, b or c"
+ */
+fn enumerate(words: &[String]) -> String {
+    match words.split_last() {
+        Some((last, [])) => last.clone(),
+        Some((last, rest)) => format!("{} or {}", rest.join(", "), last),
+        None => String::new(),
+    }
+}
+
+/**
+ * End of the token starting at `pos`, used to underline something more
+ * than a single character
+ */
+fn token_end(code: &str, pos: usize) -> usize {
+    let rest = &code[pos..];
+    let is_word = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+    match rest.chars().next() {
+        Some(c) if is_word(c) => {
+            pos + rest.find(|c: char| !is_word(c)).unwrap_or(rest.len())
+        },
+        Some(c) if !c.is_whitespace() => pos + c.len_utf8(),
+        _ => pos,
+    }
+}
+
+fn line_col(code: &str, pos: usize) -> (usize, usize) {
+    let before = &code[..pos];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+    (line, before[line_start..].chars().count() + 1)
+}
@@ -5,8 +5,12 @@ extern crate pest_derive;
 extern crate lazy_static;
 
 pub mod ast;
+mod diagnostic;
 mod parser;
+mod span;
 
+pub use diagnostic::ParseDiagnostic;
+pub use span::{Span, FileId, LineIndex};
 pub use parser::{
     LangParser,
     parse
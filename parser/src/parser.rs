@@ -1,13 +1,234 @@
 use pest::Parser;
+use pest::iterators::{Pair, Pairs};
 use ast::File;
 use ast::FromPair;
+use ast::{decode_escape, parse_integer, parse_float};
+use diagnostic::ParseDiagnostic;
+use span::FileId;
+use std::collections::HashSet;
 
 #[derive(Parser)]
 #[grammar = "grammar.pest"]
 pub struct LangParser;
 
-pub fn parse<'a>(code: &'a str) -> File {
-    let f = LangParser::parse(Rule::file, code).expect("cannot parse string")
-            .next().unwrap();
-    File::from_pair(f)
+/**
+ * Stop trying to recover after this many syntax errors in a single file
+ */
+const MAX_PARSE_ERRORS: usize = 20;
+
+/**
+ * Parses a whole source file.
+ * On a syntax error the faulty class member, statement or declaration is
+ * skipped and parsing starts over so that every error of the file gets
+ * reported at once.
+ * The spans of the nodes refer to the file through `file_id`.
+ */
+pub fn parse(file_id: FileId, file_path: &str, code: &str) -> Result<File, Vec<ParseDiagnostic>> {
+    let mut diagnostics: Vec<ParseDiagnostic> = vec![];
+    let mut source = String::from(code);
+
+    loop {
+        match LangParser::parse(Rule::file, &source) {
+            Ok(mut pairs) => {
+                diagnostics.append(&mut check_litterals(file_id, file_path, code, pairs.clone()));
+                if diagnostics.is_empty() {
+                    return Ok(File::from_pair(pairs.next().unwrap(), file_id));
+                }
+                return Err(diagnostics);
+            },
+            Err(error) => {
+                let pos = match error.location {
+                    pest::error::InputLocation::Pos(pos) => pos,
+                    pest::error::InputLocation::Span((start, _)) => start,
+                };
+                let missing_terminator = match &error.variant {
+                    pest::error::ErrorVariant::ParsingError { positives, .. } => {
+                        positives.iter().any(is_terminator)
+                    },
+                    _ => false,
+                };
+                diagnostics.push(ParseDiagnostic::from_error(file_id, file_path, code, &error));
+                if diagnostics.len() >= MAX_PARSE_ERRORS
+                        || !skip_faulty_item(&mut source, pos, missing_terminator) {
+                    return Err(diagnostics);
+                }
+            }
+        }
+    }
+}
+
+/**
+ * Reports the litterals that are syntactically right but still cannot be
+ * built, such as a `\u{...}` escape that is not a valid character or an
+ * integer too large for its type
+ */
+fn check_litterals(file_id: FileId, file_path: &str, code: &str, pairs: Pairs<Rule>) -> Vec<ParseDiagnostic> {
+    let mut diagnostics: Vec<ParseDiagnostic> = vec![];
+    let negated = negated_integers(pairs.clone());
+    for pair in pairs.flatten() {
+        let error = match pair.as_rule() {
+            Rule::escape if decode_escape(pair.as_str()).is_none() => {
+                Some(format!("`{}` is not a valid unicode character", pair.as_str()))
+            },
+            Rule::integer => parse_integer(pair.as_str(), negated.contains(&pair.as_span().start())).err(),
+            Rule::float => parse_float(pair.as_str()).err(),
+            _ => None
+        };
+        if let Some(message) = error {
+            let span = pair.as_span();
+            diagnostics.push(ParseDiagnostic::from_span(file_id, file_path, code, span.start(), span.end(), message));
+        }
+    }
+
+    diagnostics
+}
+
+/**
+ * Start positions of the integer litterals making up the whole operand of
+ * a unary minus: `-128i8` but not `-128i8.abs()`
+ */
+fn negated_integers(pairs: Pairs<Rule>) -> HashSet<usize> {
+    let mut negated: HashSet<usize> = HashSet::new();
+    for pair in pairs.flatten().filter(|p| p.as_rule() == Rule::unary_expression) {
+        let inner: Vec<Pair<Rule>> = pair.into_inner().collect();
+        if let [.., op, operand] = inner.as_slice() {
+            let parts: Vec<Pair<Rule>> = operand.clone().into_inner().collect();
+            if let (Rule::neg_op, [integer]) = (op.as_rule(), parts.as_slice()) {
+                if integer.as_rule() == Rule::integer {
+                    negated.insert(integer.as_span().start());
+                }
+            }
+        }
+    }
+
+    negated
+}
+
+fn is_terminator(rule: &Rule) -> bool {
+    matches!(rule, Rule::field_end | Rule::method_end | Rule::stmt_end | Rule::import_end)
+}
+
+/**
+ * Blanks out the class member, statement or top-level declaration that
+ * contains the error at `pos`. Newlines are kept and every other byte is
+ * replaced by a space so that positions of the remaining code don't move.
+ * Returns false when nothing can be skipped, the namespace declaration
+ * being mandatory the parser cannot recover from an error in it.
+ */
+fn skip_faulty_item(source: &mut String, pos: usize, missing_terminator: bool) -> bool {
+    let bytes = source.as_bytes();
+    let boundaries = scan_boundaries(bytes);
+
+    // the item starts right after the last boundary before the error
+    let start = match boundaries.iter().rev().find(|&&(i, _)| i < pos) {
+        Some(&(i, _)) => i + 1,
+        None => return false,
+    };
+    // when only a `;` is missing the item ends where the error is, otherwise
+    // it goes up to the next `;` or up to the end of the next body
+    let mut end = if missing_terminator { pos } else { start };
+    if end <= start {
+        let mut depth = 0;
+        end = bytes.len();
+        for &(i, c) in boundaries.iter().filter(|&&(i, _)| i >= pos) {
+            match c {
+                b'{' => depth += 1,
+                b'}' if depth == 0 => {
+                    end = i;
+                    break;
+                },
+                b'}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        end = i + 1;
+                        break;
+                    }
+                },
+                b';' if depth == 0 => {
+                    end = i + 1;
+                    break;
+                },
+                _ => {}
+            }
+        }
+    }
+    if end <= start || source[start..end].trim().is_empty() {
+        return false;
+    }
+
+    let blank: String = source[start..end].bytes()
+            .map(|b| if b == b'\n' || b == b'\r' { b as char } else { ' ' })
+            .collect();
+    source.replace_range(start..end, &blank);
+    true
+}
+
+/**
+ * Positions of the `;`, `{` and `}` that are not inside a comment, a string
+ * or a character litteral
+ */
+fn scan_boundaries(bytes: &[u8]) -> Vec<(usize, u8)> {
+    let mut boundaries: Vec<(usize, u8)> = vec![];
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+            },
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i += 2;
+                while i < bytes.len() && !(bytes[i] == b'*' && bytes.get(i + 1) == Some(&b'/')) {
+                    i += 1;
+                }
+                i += 1;
+            },
+            b'"' if bytes[i..].starts_with(b"\"\"\"") => {
+                i += 3;
+                while i < bytes.len() && !bytes[i..].starts_with(b"\"\"\"") {
+                    if bytes[i] == b'\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+                i += 2;
+            },
+            b'r' if is_raw_string_start(bytes, i) => {
+                // r##"..."## ends with a quote followed by as many #
+                let hashes = bytes[i + 1..].iter().take_while(|&&b| b == b'#').count();
+                i += hashes + 2;
+                while i < bytes.len() && !(bytes[i] == b'"'
+                        && bytes[i + 1..].iter().take_while(|&&b| b == b'#').count() >= hashes) {
+                    i += 1;
+                }
+                i += hashes;
+            },
+            quote @ b'"' | quote @ b'\'' => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != quote && bytes[i] != b'\n' {
+                    if bytes[i] == b'\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+            },
+            c @ b';' | c @ b'{' | c @ b'}' => {
+                boundaries.push((i, c));
+            },
+            _ => {}
+        }
+        i += 1;
+    }
+
+    boundaries
+}
+
+fn is_raw_string_start(bytes: &[u8], i: usize) -> bool {
+    let is_ident_char = |b: u8| b.is_ascii_alphanumeric() || b == b'_' || b == b'$';
+    if i > 0 && is_ident_char(bytes[i - 1]) {
+        return false;
+    }
+    let hashes = bytes[i + 1..].iter().take_while(|&&b| b == b'#').count();
+    bytes.get(i + 1 + hashes) == Some(&b'"')
 }
@@ -0,0 +1,137 @@
+use super::parser::Rule;
+use pest::iterators::Pair;
+
+/**
+ * Identifies the source file a span comes from, files are numbered in the
+ * order they are given to the compiler
+ */
+pub type FileId = usize;
+
+/**
+ * A range of bytes in a source file, the end is exclusive
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Span {
+    file_id: FileId,
+    start_byte: usize,
+    end_byte: usize,
+}
+
+impl Span {
+    pub fn get_file_id(&self) -> FileId {
+        self.file_id
+    }
+
+    pub fn get_start_byte(&self) -> usize {
+        self.start_byte
+    }
+
+    pub fn get_end_byte(&self) -> usize {
+        self.end_byte
+    }
+
+    pub fn new(file_id: FileId, start_byte: usize, end_byte: usize) -> Self {
+        Span {
+            file_id,
+            start_byte,
+            end_byte,
+        }
+    }
+
+    /**
+     * Span of the text matched by a grammar rule
+     */
+    pub(crate) fn from_pair(pair: &Pair<Rule>, file_id: FileId) -> Self {
+        let span = pair.as_span();
+        Span {
+            file_id,
+            start_byte: span.start(),
+            end_byte: span.end(),
+        }
+    }
+
+    /**
+     * Span going from the start of this one to the end of `other`
+     */
+    pub fn to(&self, other: Span) -> Span {
+        Span {
+            file_id: self.file_id,
+            start_byte: self.start_byte.min(other.start_byte),
+            end_byte: self.end_byte.max(other.end_byte),
+        }
+    }
+}
+
+/**
+ * The text of a source file along with the position of its lines, used to
+ * turn byte offsets into line and column positions
+ */
+#[derive(Clone, Debug)]
+pub struct LineIndex {
+    text: String,
+    // byte offset of the first character of each line
+    line_starts: Vec<usize>,
+}
+
+impl LineIndex {
+    pub fn get_text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn new(text: &str) -> Self {
+        let mut line_starts = vec![0];
+        for (i, b) in text.bytes().enumerate() {
+            if b == b'\n' {
+                line_starts.push(i + 1);
+            }
+        }
+
+        LineIndex {
+            text: String::from(text),
+            line_starts,
+        }
+    }
+
+    /**
+     * (line, column) of a byte offset, both starting at 1, the column
+     * counts characters
+     */
+    pub fn line_col(&self, byte: usize) -> (usize, usize) {
+        let line = match self.line_starts.binary_search(&byte) {
+            Ok(line) => line,
+            Err(next_line) => next_line - 1,
+        };
+        let line_start = self.line_starts[line];
+        (line + 1, self.text[line_start..byte].chars().count() + 1)
+    }
+
+    /**
+     * (line, column) where a span starts
+     */
+    pub fn start_of(&self, span: &Span) -> (usize, usize) {
+        self.line_col(span.start_byte)
+    }
+
+    /**
+     * Byte offset of the first character of a line, lines starting at 1
+     */
+    pub fn line_start(&self, line: usize) -> usize {
+        self.line_starts[line - 1]
+    }
+
+    /**
+     * Text of a line without its line break, lines starting at 1
+     */
+    pub fn get_line(&self, line: usize) -> &str {
+        let start = self.line_starts[line - 1];
+        let end = match self.line_starts.get(line) {
+            Some(&next_start) => next_start,
+            None => self.text.len(),
+        };
+        self.text[start..end].trim_end_matches(['\n', '\r'])
+    }
+
+    pub fn get_line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+}